@@ -2,7 +2,7 @@
 use std::fmt;
 use std::error::Error;
 
-//// error helper function
+/// error helper function
 //pub fn error(message: &str) -> ! {
 //    println!("");
 //    eprintln!("{}", message);
@@ -1,21 +1,39 @@
-use crate::parser::{ Node, NodeKind };
+use crate::parser::{ Node, NodeKind, Relocation };
+use crate::ty::{Ty, ArgClass, ArgLoc, Reg, arg_locs};
 
 use crate::cc_util::CompileError;
 
 pub struct Generator {
     count: usize,
     cur_func_name: String,
+    depth: usize, // the number of values pushed on stack in the current function (to align rsp at call)
+    ret_ptr: Option<i32>, // the offset of the hidden return pointer in the current function
+    break_labels: Vec<String>, // the targets of break, the last is the innermost loop or switch
+    continue_labels: Vec<String>, // the targets of continue, the last is the innermost loop
+    switches: Vec<usize>, // the label numbers of switch statements, the last is the innermost
 }
 
 
 impl Generator {
 
     const ARGS_REGISTERS: [&str; 6] = ["rdi", "rsi", "rdx", "rcx", "r8", "r9"];
+    const ARGS_REGISTERS32: [&str; 6] = ["edi", "esi", "edx", "ecx", "r8d", "r9d"];
+    const ARGS_REGISTERS8: [&str; 6] = ["dil", "sil", "dl", "cl", "r8b", "r9b"];
+
+    // switch with cases up to this is a compare chain
+    const SWITCH_CHAIN_MAX: usize = 4;
+    // jump table is used if at least 1 / SWITCH_TABLE_DENSITY of the range has cases
+    const SWITCH_TABLE_DENSITY: i64 = 3;
 
     pub fn new() -> Generator {
         Generator {
             count: 0,
             cur_func_name: String::from(""),
+            depth: 0,
+            ret_ptr: None,
+            break_labels: Vec::new(),
+            continue_labels: Vec::new(),
+            switches: Vec::new(),
         }
     }
 
@@ -37,102 +55,287 @@ impl Generator {
                 Some(n) => n,
                 None => return Ok(()),
             };
-            let NodeKind::FuncDef { name, r_type:_, params, stack_size, block } = (*node).kind else {
-                return Err(CompileError::new(&["a top-level element must be function definition"]));
-            };
+            match node.kind {
+                NodeKind::FuncDef { name, r_type:_, params, va_area, ret_ptr, stack_size, block, is_static } => {
+                    // static function is not visible from other files
+                    if !is_static {
+                        println!(".globl {}", &name);
+                    }
+                    self.gen_function(name, params, va_area, ret_ptr, stack_size, block)?
+                },
+                NodeKind::GlobalDef { name, ty, data, relocs, is_static } => Self::gen_global(name, ty, data, relocs, is_static),
+                _ => return Err(CompileError::new(&["a top-level element must be function definition"])),
+            }
+        }
 
-            self.cur_func_name = name.to_string();
+        Ok(())
+    }
 
-            // output func area
-            println!(".globl {}", &name);
-            println!("{}:", &name);
+    fn gen_function(&mut self, name: String, params: Vec<Option<Box<Node>>>, va_area: Option<i32>, ret_ptr: Option<i32>, stack_size: i32, block: Option<Box<Node>>) -> Result<(), CompileError> {
+
+        self.cur_func_name = name.to_string();
+        self.depth = 0;
+        self.ret_ptr = ret_ptr;
+
+        // output func area
+        println!(".text");
+        println!("{}:", &name);
+        println!();
+
+        // output prologue
+        println!("  push rbp");
+        println!("  mov rbp, rsp");
+        println!("  sub rsp, {}", stack_size);
+        println!();
+
+        // register save area of variadic function (general purpose registers from +0, vector registers from +48)
+        if let Some(offset) = va_area {
+            for (i, register) in Self::ARGS_REGISTERS.iter().enumerate() {
+                println!("  mov [rbp-{}], {}", offset - i as i32 * 8, register);
+            }
+            for i in 0..8 {
+                println!("  movsd [rbp-{}], xmm{}", offset - 48 - i * 16, i);
+            }
             println!();
+        }
 
-            // output prologue
-            println!("  push rbp");
-            println!("  mov rbp, rsp");
-            println!("  sub rsp, {}", stack_size);
+        // the buffer for returned struct/union is given in rdi
+        if let Some(offset) = ret_ptr {
+            println!("  mov [rbp-{}], rdi", offset);
             println!();
+        }
 
-            // output params
-            let mut paramc = 0;
+        // output params
+        let params: Vec<(i32, Ty)> = params.iter().filter_map(|param| match &param.as_deref()?.kind {
+            NodeKind::Lvar { name:_, offset, ty } => Some((*offset, ty.clone())),
+            _ => None,
+        }).collect();
+        let tys: Vec<Ty> = params.iter().map(|(_, ty)| ty.clone()).collect();
+        let layout = arg_locs(&tys, if ret_ptr.is_some() { 1 } else { 0 });
 
-            for param in &params {
+        // the ones in registers are saved at first, because copying from stack uses rdi and r8
+        let (in_regs, on_stack): (Vec<_>, Vec<_>) = params.iter().zip(layout.locs).partition(|(_, loc)| matches!(loc, ArgLoc::Regs(_)));
 
-                let pn = match param {
-                    Some(n) => n,
-                    None => continue,
-                };
-                if let NodeKind::Lvar { name:_, offset, ty:_ } = (**pn).kind {
+        for ((offset, ty), loc) in in_regs {
+            let ArgLoc::Regs(regs) = loc else { continue };
 
-                    println!("  mov [rbp-{}], {}", offset, &Self::ARGS_REGISTERS[paramc]);
-                    println!();
-                    paramc = paramc + 1;
-                    if paramc == Self::ARGS_REGISTERS.len() { // only 6 arguments are accepted
-                        break;
+            // struct/union is passed by eightbytes
+            if ty.is_aggregate() {
+                println!("  lea rax, [rbp-{}]", offset);
+                for (i, reg) in regs.into_iter().enumerate() {
+                    let size = (ty.size() - i as i32 * 8).min(8);
+                    match reg {
+                        Reg::Gp(reg) => Self::store_eightbyte("rax", Self::ARGS_REGISTERS[reg], Self::ARGS_REGISTERS8[reg], i as i32 * 8, size),
+                        Reg::Fp(reg) => Self::store_fp("rax", reg, i as i32 * 8, size),
                     }
-                };
+                }
+                println!();
+                continue;
             }
 
-            // output body
-            self.generate(block)?;
-            // TODO need pop rax?
-
-            // output epilogue
-            println!(".L.return.{}:", &name);
-            println!("  mov rsp, rbp");
-            println!("  pop rbp");
-            println!("  ret");
+            match regs[0] {
+                Reg::Fp(reg) => Self::store_fp("rbp", reg, -offset, ty.size()),
+                Reg::Gp(reg) => {
+                    let register = match ty.size() {
+                        1 => &Self::ARGS_REGISTERS8[reg],
+                        4 => &Self::ARGS_REGISTERS32[reg],
+                        _ => &Self::ARGS_REGISTERS[reg],
+                    };
+                    println!("  mov [rbp-{}], {}", offset, register);
+                },
+            }
             println!();
+        }
+
+        for ((offset, ty), loc) in on_stack {
+            let ArgLoc::Stack(slot) = loc else { continue };
 
+            // on the stack of caller, over the return address and saved rbp (8 bytes each)
+            let stack_offset = 16 + slot * 8;
+            if ty.is_aggregate() {
+                println!("  lea rdi, [rbp+{}]", stack_offset);
+                println!("  lea rax, [rbp-{}]", offset);
+                Self::copy_bytes(ty.size(), 0);
+                println!();
+                continue;
+            }
+
+            println!("  mov rax, [rbp+{}]", stack_offset);
+            match ty.size() {
+                1 => println!("  mov [rbp-{}], al", offset),
+                4 => println!("  mov [rbp-{}], eax", offset),
+                _ => println!("  mov [rbp-{}], rax", offset),
+            }
+            println!();
         }
 
+        // output body
+        self.generate(block)?;
+
+        // output epilogue
+        println!(".L.return.{}:", &name);
+        println!("  mov rsp, rbp");
+        println!("  pop rbp");
+        println!("  ret");
+        println!();
+
+        // every statement pops what it pushes
+        debug_assert_eq!(self.depth, 0);
+
         Ok(())
     }
 
+    // global variable (e.g. string literal), the one without initial value is in .bss, and const one is in .rodata
+    fn gen_global(name: String, ty: Ty, data: Option<Vec<u8>>, relocs: Vec<Relocation>, is_static: bool) {
+        if !is_static {
+            println!(".globl {}", &name);
+        }
+        match data {
+            // the addresses are written when the program is loaded, so they can't be in .rodata
+            _ if ty.is_const() && relocs.is_empty() => println!(".section .rodata"),
+            Some(_) => println!(".data"),
+            None => println!(".bss"),
+        }
+        println!(".align {}", ty.align());
+        println!("{}:", &name);
+        match data {
+            Some(bytes) => {
+                let mut i = 0;
+                while i < bytes.len() {
+                    // the address is 8 bytes (e.g. ".quad x+4")
+                    if let Some(reloc) = relocs.iter().find(|r| r.offset == i) {
+                        println!("  .quad {}{:+}", reloc.label, reloc.addend);
+                        i += 8;
+                        continue;
+                    }
+                    println!("  .byte {}", bytes[i]);
+                    i += 1;
+                }
+            },
+            None => println!("  .zero {}", ty.size()),
+        }
+        println!();
+    }
+
     fn generate(&mut self, nd: Option<Box<Node>>) -> Result<(), CompileError> {
 
-        let node = match nd {
+        let mut node = match nd {
             Some(n) => n,
             None => return Ok(()),
         };
+        let ty = node.ty().clone();
 
-        match (*node).kind {
-            NodeKind::If { cond, then, else_then } => {
-                self.count = self.count + 1;
+        match node.kind {
+            NodeKind::If { mut cond, then, else_then } => {
+                self.count += 1;
+                let c = self.count; // self.count is changed by nested statements
+                let cond_ty = cond.as_mut().unwrap().ty().clone();
                 self.generate(cond)?;
-                println!("  pop rax");
-                println!("  cmp rax, 0");
-                println!("  je .L.else.{}", self.count);
+                self.pop("rax");
+                Self::cmp_zero(&cond_ty);
+                println!("  je .L.else.{}", c);
                 self.generate(then)?;
-                println!("  jmp .L.end.{}", self.count);
-                println!(".L.else.{}:", self.count);
-                if let Some(_) = else_then {
+                println!("  jmp .L.end.{}", c);
+                println!(".L.else.{}:", c);
+                if else_then.is_some() {
                     self.generate(else_then)?;
                 }
-                println!(".L.end.{}:", self.count);
+                println!(".L.end.{}:", c);
                 println!();
                 return Ok(());
             },
-            NodeKind::For { init, cond, inc, then } => {
-                self.count = self.count + 1;
+            NodeKind::For { init, mut cond, inc, then } => {
+                self.count += 1;
+                let c = self.count; // self.count is changed by nested statements
                 self.generate(init)?;
-                println!(".L.begin.{}:", self.count);
-                if let Some(_) = cond {
+                println!(".L.begin.{}:", c);
+                if let Some(cond_node) = cond.as_mut() {
+                    let cond_ty = cond_node.ty().clone();
                     self.generate(cond)?;
-                    println!("  pop rax");
-                    println!("  cmp rax, 0");
-                    println!("  je .L.end.{}", self.count);
+                    self.pop("rax");
+                    Self::cmp_zero(&cond_ty);
+                    println!("  je .L.end.{}", c);
                 }
-                self.generate(then)?;
-                if let Some(_) = inc {
+                self.gen_loop_body(then, c)?;
+                if inc.is_some() {
                     self.generate(inc)?;
                 }
-                println!("  jmp .L.begin.{}", self.count);
-                println!(".L.end.{}:", self.count);
+                println!("  jmp .L.begin.{}", c);
+                println!(".L.end.{}:", c);
+                println!();
+                return Ok(());
+
+            },
+            NodeKind::DoWhile { then, mut cond } => {
+                self.count += 1;
+                let c = self.count; // self.count is changed by nested statements
+                let cond_ty = cond.as_mut().unwrap().ty().clone();
+                println!(".L.begin.{}:", c);
+                self.gen_loop_body(then, c)?;
+                self.generate(cond)?;
+                self.pop("rax");
+                Self::cmp_zero(&cond_ty);
+                println!("  jne .L.begin.{}", c);
+                println!(".L.end.{}:", c);
                 println!();
                 return Ok(());
+            },
+            NodeKind::Switch { cond, body, mut cases, default } => {
+                self.count += 1;
+                let c = self.count; // self.count is changed by nested statements
+                self.generate(cond)?;
+                self.pop("rax");
 
+                let default_label = match default {
+                    Some(index) => format!(".L.case.{}.{}", c, index),
+                    None => format!(".L.end.{}", c),
+                };
+                cases.sort();
+                self.gen_switch_dispatch(&cases, c, &default_label);
+
+                self.break_labels.push(format!(".L.end.{}", c));
+                self.switches.push(c);
+                self.generate(body)?;
+                self.switches.pop();
+                self.break_labels.pop();
+                println!(".L.end.{}:", c);
+                println!();
+                return Ok(());
+            },
+            NodeKind::Case { index, body } => {
+                println!(".L.case.{}.{}:", self.switches.last().unwrap(), index);
+                self.generate(body)?;
+                return Ok(());
+            },
+            // labels are local to the function
+            NodeKind::Label { name, body } => {
+                println!(".L.label.{}.{}:", &self.cur_func_name, name);
+                self.generate(body)?;
+                return Ok(());
+            },
+            NodeKind::Goto { name } => {
+                println!("  jmp .L.label.{}.{}", &self.cur_func_name, name);
+                return Ok(());
+            },
+            NodeKind::GotoPtr { target } => {
+                self.generate(target)?;
+                self.pop("rax");
+                println!("  jmp rax");
+                return Ok(());
+            },
+            NodeKind::LabelAddr { name } => {
+                println!("  lea rax, .L.label.{}.{}[rip]", &self.cur_func_name, name);
+                self.push("rax");
+                println!();
+                return Ok(());
+            },
+            NodeKind::Break => {
+                println!("  jmp {}", self.break_labels.last().unwrap());
+                return Ok(());
+            },
+            NodeKind::Continue => {
+                println!("  jmp {}", self.continue_labels.last().unwrap());
+                return Ok(());
             },
             NodeKind::Block { body } => {
                 for b in body {
@@ -140,23 +343,72 @@ impl Generator {
                 }
                 return Ok(());
             },
-            NodeKind::Return { lhs } => {
+            NodeKind::MemZero { offset, size } => {
+                // rep stosb stores al to [rdi] rcx times
+                println!("  mov rdi, rbp");
+                println!("  sub rdi, {}", offset);
+                println!("  mov rcx, {}", size);
+                println!("  mov al, 0");
+                println!("  rep stosb");
+                println!();
+                return Ok(());
+            },
+            NodeKind::ExprStmt { lhs } => {
+                self.generate(lhs)?;
+                self.pop("rax");
+                println!();
+                return Ok(());
+            },
+            NodeKind::Return { mut lhs } => {
+                let r_type = lhs.as_mut().unwrap().ty().clone();
                 self.generate(lhs)?;
-                println!("  pop rax");
+                self.pop("rax");
+                if r_type.is_aggregate() {
+                    self.gen_ret_aggregate(&r_type);
+                }
+                if r_type.is_flonum() {
+                    println!("  movq xmm0, rax");
+                }
                 println!("  jmp .L.return.{}", &self.cur_func_name);
                 println!();
                 return Ok(());
             },
             NodeKind::Num { value } => {
-                println!("  push {}", value);
+                self.push(&value.to_string());
                 println!();
                 return Ok(());
             },
-            NodeKind::Lvar { .. } => {
+            NodeKind::FNum { value, ty:_ } => {
+                // the bit pattern of floating point number is kept in rax (float is in the lower 32 bits)
+                match ty {
+                    Ty::Float => println!("  mov rax, {}", (value as f32).to_bits()),
+                    _ => println!("  mov rax, {}", value.to_bits()),
+                }
+                self.push("rax");
+                println!();
+                return Ok(());
+            },
+            NodeKind::Neg { lhs } => {
+                self.generate(lhs)?;
+                self.pop("rax");
+                // flip the sign bit of floating point number
+                match ty {
+                    Ty::Float => println!("  btc rax, 31"),
+                    Ty::Double => println!("  btc rax, 63"),
+                    _ => {
+                        println!("  neg rax");
+                        Self::wrap_unsigned(&ty);
+                    },
+                }
+                self.push("rax");
+                println!();
+                return Ok(());
+            },
+            NodeKind::Lvar { .. } | NodeKind::Gvar { .. } | NodeKind::Member { .. } | NodeKind::FuncRef { .. } => {
                 self.gen_lval(node)?;
-                println!("  pop rax");
-                println!("  mov rax, [rax]");
-                println!("  push rax");
+                self.pop("rax");
+                Self::load(&ty);
+                self.push("rax");
                 println!();
                 return Ok(());
             },
@@ -165,32 +417,44 @@ impl Generator {
                     self.gen_lval(lhs_node)?;
                 };
                 self.generate(rhs)?;
-                println!("  pop rdi");
-                println!("  pop rax");
-                println!("  mov [rax], rdi");
-                println!("  push rdi");
+                self.pop("rdi");
+                self.pop("rax");
+                Self::store(&ty);
+                self.push("rdi");
                 println!();
                 return Ok(());
             },
-            NodeKind::FuncCall { name, args } => {
+            NodeKind::FuncCall { name, args, ty:_, ret_buffer } => {
 
-                let mut argc = 0;
+                let first_reg = Self::first_arg_reg(&ty);
+                let (regs, fp, stack_args) = self.push_args(args, first_reg)?;
+                self.pop_args(regs);
+                Self::set_ret_buffer(&ty, ret_buffer);
 
-                for arg in args {
-                    self.generate(arg)?;
-                    argc = argc + 1;
-                    if argc == Self::ARGS_REGISTERS.len() { // only 6 arguments are accepted
-                        break;
-                    }
-                }
+                // al is the number of vector registers used for variadic arguments
+                println!("  mov rax, {}", fp);
+                println!("  call {}", &name);
+                self.clean_stack_args(stack_args);
+                Self::gen_ret_value(&ty, ret_buffer);
+                self.push("rax");
+                println!();
+                return Ok(());
+            },
+            NodeKind::FuncPtrCall { func, args, ret_buffer } => {
 
-                for i in (0..argc).rev() { // reverse, because stack is FIFO
-                    println!("  pop {}", &Self::ARGS_REGISTERS[i]);
-                }
+                // the address of function is on the top of arguments
+                let first_reg = Self::first_arg_reg(&ty);
+                let (regs, fp, stack_args) = self.push_args(args, first_reg)?;
+                self.generate(func)?;
+                self.pop("r10");
+                self.pop_args(regs);
+                Self::set_ret_buffer(&ty, ret_buffer);
 
-                println!("  mov rax, 0");
-                println!("  call {}", &name);
-                println!("  push rax");
+                println!("  mov rax, {}", fp);
+                println!("  call r10");
+                self.clean_stack_args(stack_args);
+                Self::gen_ret_value(&ty, ret_buffer);
+                self.push("rax");
                 println!();
                 return Ok(());
             },
@@ -202,37 +466,182 @@ impl Generator {
                 println!();
                 return Ok(());
             },
+            NodeKind::VaStart { ap, gp_offset, fp_offset, overflow_offset, area_offset } => {
+                // initialize the element of va_list
+                self.generate(ap)?;
+                self.pop("rax");
+                println!("  mov dword ptr [rax], {}", gp_offset);
+                println!("  mov dword ptr [rax+4], {}", fp_offset);
+                println!("  lea rdi, [rbp+{}]", overflow_offset);
+                println!("  mov [rax+8], rdi");
+                println!("  lea rdi, [rbp-{}]", area_offset);
+                println!("  mov [rax+16], rdi");
+                self.push("rax");
+                println!();
+                return Ok(());
+            },
+            NodeKind::VaArg { ap, ty:_ } => {
+                self.count += 1;
+                let c = self.count;
+                self.generate(ap)?;
+                self.pop("rax");
+
+                // double is taken by fp_offset (vector registers are saved by 16 bytes after general purpose registers)
+                let (field, end, step) = match ty.is_flonum() {
+                    true => (4, 6 * 8 + 8 * 16, 16),
+                    false => (0, 6 * 8, 8),
+                };
+
+                // take the next argument from the register save area while left, and from the stack after that
+                println!("  cmp dword ptr [rax+{}], {}", field, end);
+                println!("  jge .L.va_stack.{}", c);
+                println!("  movsxd rdi, dword ptr [rax+{}]", field);
+                println!("  add rdi, [rax+16]");
+                println!("  add dword ptr [rax+{}], {}", field, step);
+                println!("  jmp .L.va_load.{}", c);
+                println!(".L.va_stack.{}:", c);
+                println!("  mov rdi, [rax+8]");
+                println!("  add qword ptr [rax+8], 8");
+                println!(".L.va_load.{}:", c);
+                println!("  mov rax, rdi");
+                Self::load(&ty);
+                self.push("rax");
+                println!();
+                return Ok(());
+            },
+            NodeKind::Cond { mut cond, then, els, ty:_ } => {
+                self.count += 1;
+                let c = self.count;
+                let cond_ty = cond.as_mut().unwrap().ty().clone();
+                self.generate(cond)?;
+                self.pop("rax");
+                Self::cmp_zero(&cond_ty);
+                println!("  je .L.else.{}", c);
+                self.generate(then)?;
+                // only one of then and els is pushed at runtime
+                self.depth -= 1;
+                println!("  jmp .L.end.{}", c);
+                println!(".L.else.{}:", c);
+                self.generate(els)?;
+                println!(".L.end.{}:", c);
+                println!();
+                return Ok(());
+            },
+            NodeKind::CompoundLiteral { init, var } => {
+                self.generate(init)?;
+                self.generate(var)?;
+                return Ok(());
+            },
+            NodeKind::Comma { lhs, rhs } => {
+                self.generate(lhs)?;
+                self.pop("rax");
+                self.generate(rhs)?;
+                return Ok(());
+            },
+            NodeKind::LogAnd { lhs, rhs } => return self.gen_logical(lhs, rhs, true),
+            NodeKind::LogOr { lhs, rhs } => return self.gen_logical(lhs, rhs, false),
+            NodeKind::BitNot { lhs } => {
+                self.generate(lhs)?;
+                self.pop("rax");
+                println!("  not rax");
+                Self::wrap_unsigned(&ty);
+                self.push("rax");
+                println!();
+                return Ok(());
+            },
+            NodeKind::Not { mut lhs } => {
+                let lhs_ty = lhs.as_mut().unwrap().ty().clone();
+                self.generate(lhs)?;
+                self.pop("rax");
+                Self::cmp_zero(&lhs_ty);
+                println!("  sete al");
+                println!("  movzb rax, al");
+                self.push("rax");
+                println!();
+                return Ok(());
+            },
+            NodeKind::Cast { mut lhs, ty:_ } => {
+                let from = lhs.as_mut().unwrap().ty().clone();
+                self.generate(lhs)?;
+                self.pop("rax");
+                Self::cast(&from, &ty);
+                self.push("rax");
+                println!();
+                return Ok(());
+            },
             NodeKind::Deref { lhs } => {
                 self.generate(lhs)?;
-                println!("  pop rax");
-                println!("  mov rax, [rax]");
-                println!("  push rax");
+                self.pop("rax");
+                Self::load(&ty);
+                self.push("rax");
                 println!();
                 return Ok(());
             },
             _ => {}
         }
 
+        // arithmetic and comparison of floating point numbers use vector registers
+        let operand_ty = match &mut node.kind {
+            NodeKind::Add { lhs, .. } | NodeKind::Sub { lhs, .. } | NodeKind::Mul { lhs, .. } | NodeKind::Div { lhs, .. } |
+            NodeKind::Eq { lhs, .. } | NodeKind::Ne { lhs, .. } | NodeKind::Lt { lhs, .. } | NodeKind::Le { lhs, .. } => lhs.as_mut().unwrap().ty().clone(),
+            _ => ty.clone(),
+        };
+        if operand_ty.is_flonum() {
+            return self.gen_flonum_binary(node.kind, &operand_ty);
+        }
 
-
-
-        match (*node).kind {
+        match node.kind {
             NodeKind::Add { lhs, rhs } => { 
                 self.gen_binary(lhs, rhs)?;
-                println!("  add rax, rdi")
+                println!("  add rax, rdi");
+                Self::wrap_unsigned(&ty);
             },
             NodeKind::Sub { lhs, rhs } => {
                 self.gen_binary(lhs, rhs)?;
-                println!("  sub rax, rdi")
+                println!("  sub rax, rdi");
+                Self::wrap_unsigned(&ty);
             },
             NodeKind::Mul { lhs, rhs } => {
                 self.gen_binary(lhs, rhs)?;
-                println!("  imul rax, rdi")
+                println!("  imul rax, rdi");
+                Self::wrap_unsigned(&ty);
             },
             NodeKind::Div { lhs, rhs } => { 
                 self.gen_binary(lhs, rhs)?;
-                println!("  cqo");
-                println!("  idiv rdi");
+                Self::gen_div(&ty);
+            },
+            NodeKind::Mod { lhs, rhs } => {
+                self.gen_binary(lhs, rhs)?;
+                Self::gen_div(&ty);
+                println!("  mov rax, rdx");
+            },
+            NodeKind::BitAnd { lhs, rhs } => {
+                self.gen_binary(lhs, rhs)?;
+                println!("  and rax, rdi");
+            },
+            NodeKind::BitOr { lhs, rhs } => {
+                self.gen_binary(lhs, rhs)?;
+                println!("  or rax, rdi");
+            },
+            NodeKind::BitXor { lhs, rhs } => {
+                self.gen_binary(lhs, rhs)?;
+                println!("  xor rax, rdi");
+            },
+            // the bits shifted over int are dropped by sign (or zero) extension
+            NodeKind::Shl { lhs, rhs } => {
+                self.gen_binary(lhs, rhs)?;
+                println!("  mov rcx, rdi");
+                println!("  shl rax, cl");
+                Self::extend_integer(&ty);
+            },
+            // unsigned integer is zero extended, so the logical shift fills the upper bits with 0
+            NodeKind::Shr { lhs, rhs } => {
+                self.gen_binary(lhs, rhs)?;
+                println!("  mov rcx, rdi");
+                match ty.is_unsigned() {
+                    true => println!("  shr rax, cl"),
+                    false => println!("  sar rax, cl"),
+                }
             },
             NodeKind::Eq { lhs, rhs } => {
                 self.gen_binary(lhs, rhs)?;
@@ -249,43 +658,366 @@ impl Generator {
             NodeKind::Lt { lhs, rhs } => {
                 self.gen_binary(lhs, rhs)?;
                 println!("  cmp rax, rdi");
-                println!("  setl al");
+                match operand_ty.is_unsigned() {
+                    true => println!("  setb al"),
+                    false => println!("  setl al"),
+                }
                 println!("  movzb rax, al");
             },
             NodeKind::Le { lhs, rhs } => {
                 self.gen_binary(lhs, rhs)?;
                 println!("  cmp rax, rdi");
-                println!("  setle al");
+                match operand_ty.is_unsigned() {
+                    true => println!("  setbe al"),
+                    false => println!("  setle al"),
+                }
                 println!("  movzb rax, al");
             },
             _ => {}
         }
 
-        println!("  push rax");
+        self.push("rax");
         println!();
 
         Ok(())
     }
 
+    // jump to the case label for the value in rax, cases are sorted by value
+    // a few cases are compared one by one, dense cases use a jump table, and sparse cases are searched by binary search
+    fn gen_switch_dispatch(&mut self, cases: &[(i32, usize)], c: usize, default_label: &str) {
+
+        if cases.len() <= Self::SWITCH_CHAIN_MAX {
+            for (value, index) in cases {
+                println!("  cmp rax, {}", value);
+                println!("  je .L.case.{}.{}", c, index);
+            }
+            println!("  jmp {}", default_label);
+            return;
+        }
+
+        let min = cases[0].0;
+        let range = cases[cases.len() - 1].0 as i64 - min as i64 + 1;
+        if range <= cases.len() as i64 * Self::SWITCH_TABLE_DENSITY {
+            self.gen_jump_table(cases, c, default_label, min, range);
+            return;
+        }
+
+        // the middle value divides cases into lower and upper
+        self.count += 1;
+        let lower_label = format!(".L.switch_lower.{}", self.count);
+        let mid = cases.len() / 2;
+        let (value, index) = cases[mid];
+        println!("  cmp rax, {}", value);
+        println!("  je .L.case.{}.{}", c, index);
+        println!("  jl {}", lower_label);
+        self.gen_switch_dispatch(&cases[mid + 1..], c, default_label);
+        println!("{}:", lower_label);
+        self.gen_switch_dispatch(&cases[..mid], c, default_label);
+    }
+
+    // the table in .rodata has the offset of case label from the table for each value in min..min+range
+    fn gen_jump_table(&mut self, cases: &[(i32, usize)], c: usize, default_label: &str, min: i32, range: i64) {
+        self.count += 1;
+        let table = format!(".L.jump_table.{}", self.count);
+
+        // the value out of range is a big unsigned number after subtracting min
+        println!("  sub rax, {}", min);
+        println!("  cmp rax, {}", range - 1);
+        println!("  ja {}", default_label);
+        println!("  lea rdi, {}[rip]", table);
+        println!("  movsxd rax, dword ptr [rdi+rax*4]");
+        println!("  add rax, rdi");
+        println!("  jmp rax");
+
+        println!(".section .rodata");
+        println!(".align 4");
+        println!("{}:", table);
+        let mut cases = cases.iter().peekable();
+        for value in min as i64..min as i64 + range {
+            match cases.next_if(|(v, _)| *v as i64 == value) {
+                Some((_, index)) => println!("  .long .L.case.{}.{} - {}", c, index, table),
+                None => println!("  .long {} - {}", default_label, table),
+            }
+        }
+        println!(".text");
+    }
+
+    // break jumps to .L.end.c, and continue jumps to .L.continue.c, which is just after the body
+    fn gen_loop_body(&mut self, then: Option<Box<Node>>, c: usize) -> Result<(), CompileError> {
+        self.break_labels.push(format!(".L.end.{}", c));
+        self.continue_labels.push(format!(".L.continue.{}", c));
+        self.generate(then)?;
+        self.break_labels.pop();
+        self.continue_labels.pop();
+        println!(".L.continue.{}:", c);
+        Ok(())
+    }
+
     fn gen_binary(&mut self, lhs: Option<Box<Node>>, rhs: Option<Box<Node>>) -> Result<(), CompileError> {
 
         self.generate(lhs)?;
         self.generate(rhs)?;
 
-        println!("  pop rdi");
-        println!("  pop rax");
+        self.pop("rdi");
+        self.pop("rax");
+
+        Ok(())
+    }
+
+    // && and || jump to the end as soon as the result is decided by lhs (0 for &&, 1 for ||)
+    fn gen_logical(&mut self, mut lhs: Option<Box<Node>>, mut rhs: Option<Box<Node>>, is_and: bool) -> Result<(), CompileError> {
+
+        self.count += 1;
+        let c = self.count;
+        let lhs_ty = lhs.as_mut().unwrap().ty().clone();
+        let rhs_ty = rhs.as_mut().unwrap().ty().clone();
+        let (jump, decided) = match is_and {
+            true => ("je", 0),
+            false => ("jne", 1),
+        };
+
+        self.generate(lhs)?;
+        self.pop("rax");
+        Self::cmp_zero(&lhs_ty);
+        println!("  {} .L.decided.{}", jump, c);
+        self.generate(rhs)?;
+        self.pop("rax");
+        Self::cmp_zero(&rhs_ty);
+        println!("  {} .L.decided.{}", jump, c);
+        println!("  mov rax, {}", 1 - decided);
+        println!("  jmp .L.end.{}", c);
+        println!(".L.decided.{}:", c);
+        println!("  mov rax, {}", decided);
+        println!(".L.end.{}:", c);
+        self.push("rax");
+        println!();
+
+        Ok(())
+    }
+
+    // lhs is in xmm0 and rhs is in xmm1, and the result is in rax
+    fn gen_flonum_binary(&mut self, kind: NodeKind, ty: &Ty) -> Result<(), CompileError> {
+
+        let suffix = match ty {
+            Ty::Float => "ss",
+            _ => "sd",
+        };
+        let (op, lhs, rhs) = match kind {
+            NodeKind::Add { lhs, rhs } => ("add", lhs, rhs),
+            NodeKind::Sub { lhs, rhs } => ("sub", lhs, rhs),
+            NodeKind::Mul { lhs, rhs } => ("mul", lhs, rhs),
+            NodeKind::Div { lhs, rhs } => ("div", lhs, rhs),
+            NodeKind::Eq { lhs, rhs } => ("eq", lhs, rhs),
+            NodeKind::Ne { lhs, rhs } => ("ne", lhs, rhs),
+            NodeKind::Lt { lhs, rhs } => ("lt", lhs, rhs),
+            NodeKind::Le { lhs, rhs } => ("le", lhs, rhs),
+            _ => return Err(CompileError::new(&["invalid operator for floating point number"])),
+        };
+
+        self.gen_binary(lhs, rhs)?;
+        println!("  movq xmm0, rax");
+        println!("  movq xmm1, rdi");
+
+        // the comparison with NaN is unordered (PF is set), and only != is true
+        match op {
+            "eq" => {
+                println!("  ucomi{} xmm0, xmm1", suffix);
+                println!("  sete al");
+                println!("  setnp dl");
+                println!("  and al, dl");
+                println!("  movzb rax, al");
+            },
+            "ne" => {
+                println!("  ucomi{} xmm0, xmm1", suffix);
+                println!("  setne al");
+                println!("  setp dl");
+                println!("  or al, dl");
+                println!("  movzb rax, al");
+            },
+            // a < b is b > a, which is false when unordered
+            "lt" => {
+                println!("  ucomi{} xmm1, xmm0", suffix);
+                println!("  seta al");
+                println!("  movzb rax, al");
+            },
+            "le" => {
+                println!("  ucomi{} xmm1, xmm0", suffix);
+                println!("  setae al");
+                println!("  movzb rax, al");
+            },
+            _ => {
+                println!("  {}{} xmm0, xmm1", op, suffix);
+                println!("  movq rax, xmm0");
+            },
+        }
+
+        self.push("rax");
+        println!();
+
+        Ok(())
+    }
+
+
+    // evaluate arguments and place them as System V ABI.
+    // the ones passed on stack are pushed from the last one, and then the ones passed by registers are pushed from the last one,
+    // so that the value for the first register is on the top of stack, and the first one on stack is just under the registers.
+    // returns the registers to pop in order, the number of used vector registers, and the number of stack slots including padding for alignment
+    fn push_args(&mut self, args: Vec<Option<Box<Node>>>, first_reg: usize) -> Result<(Vec<Reg>, usize, usize), CompileError> {
+
+        let mut args: Vec<Box<Node>> = args.into_iter().flatten().collect();
+        let tys: Vec<Ty> = args.iter_mut().map(|arg| arg.ty().clone()).collect();
+        let layout = arg_locs(&tys, first_reg);
+        let mut stack_args = layout.stack_slots;
 
+        // rsp must be 16 byte aligned at call, so pad under the arguments left on stack if needed
+        if (self.depth + stack_args) % 2 == 1 {
+            println!("  sub rsp, 8");
+            self.depth += 1;
+            stack_args += 1;
+        }
+
+        let (in_regs, on_stack): (Vec<_>, Vec<_>) = args.into_iter().zip(tys).zip(layout.locs).partition(|(_, loc)| matches!(loc, ArgLoc::Regs(_)));
+        let regs: Vec<Reg> = in_regs.iter().flat_map(|(_, loc)| match loc {
+            ArgLoc::Regs(regs) => regs.clone(),
+            ArgLoc::Stack(_) => Vec::new(),
+        }).collect();
+        for ((arg, ty), _) in on_stack.into_iter().rev().chain(in_regs.into_iter().rev()) {
+            self.push_arg(arg, &ty)?;
+        }
+
+        Ok((regs, layout.fp, stack_args))
+    }
+
+    // push the value of argument, and struct/union is pushed as its content, so that the first eightbyte is on the top
+    fn push_arg(&mut self, arg: Box<Node>, ty: &Ty) -> Result<(), CompileError> {
+        self.generate(Some(arg))?;
+        if !ty.is_aggregate() {
+            return Ok(());
+        }
+
+        self.pop("rdi");
+        let n = ty.eightbytes();
+        println!("  sub rsp, {}", n * 8);
+        self.depth += n;
+        println!("  mov rax, rsp");
+        Self::copy_bytes(ty.size(), 0);
+        println!();
         Ok(())
     }
 
+    fn pop_args(&mut self, regs: Vec<Reg>) {
+        for reg in regs {
+            match reg {
+                Reg::Gp(reg) => self.pop(Self::ARGS_REGISTERS[reg]),
+                Reg::Fp(reg) => {
+                    self.pop("rax");
+                    println!("  movq xmm{}, rax", reg);
+                },
+            }
+        }
+    }
+
+    // rdi has the hidden pointer to the buffer for returned struct/union of MEMORY class
+    fn first_arg_reg(ty: &Ty) -> usize {
+        if ty.is_memory_class() { 1 } else { 0 }
+    }
+
+    fn set_ret_buffer(ty: &Ty, ret_buffer: Option<i32>) {
+        if let Some(offset) = ret_buffer && ty.is_memory_class() {
+            println!("  lea rdi, [rbp-{}]", offset);
+        }
+    }
+
+    // the value of call is in rax (or xmm0 for floating point number)
+    // struct/union is returned in rax, rdx, xmm0 and xmm1 by eightbytes, or in the buffer (its address is in rax), and the value is the address of the buffer
+    fn gen_ret_value(ty: &Ty, ret_buffer: Option<i32>) {
+        match ret_buffer {
+            Some(_) if ty.is_memory_class() => {},
+            // the buffer is rounded up to eightbytes
+            Some(offset) => {
+                println!("  lea rdi, [rbp-{}]", offset);
+                let (mut gp, mut fp) = (0, 0);
+                for (i, class) in ty.classes().unwrap_or_default().into_iter().enumerate() {
+                    match class {
+                        ArgClass::Integer => {
+                            println!("  mov [rdi+{}], {}", i * 8, ["rax", "rdx"][gp]);
+                            gp += 1;
+                        },
+                        ArgClass::Sse => {
+                            println!("  movsd [rdi+{}], xmm{}", i * 8, fp);
+                            fp += 1;
+                        },
+                    }
+                }
+                println!("  mov rax, rdi");
+            },
+            None if ty.is_flonum() => println!("  movq rax, xmm0"),
+            // the upper bits of the return value are undefined
+            None => Self::extend_integer(ty),
+        }
+    }
+
+    // return struct/union whose address is in rax
+    fn gen_ret_aggregate(&mut self, ty: &Ty) {
+        println!("  mov rdi, rax");
+        match self.ret_ptr {
+            // copy to the buffer given by caller, and return its address
+            Some(offset) => {
+                println!("  mov rax, [rbp-{}]", offset);
+                Self::copy_bytes(ty.size(), 0);
+            },
+            // return in rax and rdx, or xmm0 and xmm1 by the class of eightbyte
+            None => {
+                let (mut gp, mut fp) = (0, 0);
+                for (i, class) in ty.classes().unwrap_or_default().into_iter().enumerate() {
+                    let size = (ty.size() - i as i32 * 8).min(8);
+                    match class {
+                        ArgClass::Integer => {
+                            Self::load_eightbyte(["rax", "rdx"][gp], ["al", "dl"][gp], i as i32 * 8, size);
+                            gp += 1;
+                        },
+                        ArgClass::Sse => {
+                            Self::load_fp(fp, i as i32 * 8, size);
+                            fp += 1;
+                        },
+                    }
+                }
+            },
+        }
+    }
+
+    // remove arguments passed by stack after call
+    fn clean_stack_args(&mut self, stack_args: usize) {
+        if stack_args > 0 {
+            println!("  add rsp, {}", stack_args * 8);
+            self.depth -= stack_args;
+        }
+    }
+
+    fn push(&mut self, operand: &str) {
+        println!("  push {}", operand);
+        self.depth += 1;
+    }
+
+    fn pop(&mut self, register: &str) {
+        println!("  pop {}", register);
+        self.depth -= 1;
+    }
+
+
+    fn gen_lval(&mut self, mut node: Box<Node>) -> Result<(), CompileError> {
+
+        // the value of struct/union returned by function is the address of the buffer (e.g. "f().x")
+        if matches!(node.kind, NodeKind::FuncCall { .. } | NodeKind::FuncPtrCall { .. }) && node.ty().is_aggregate() {
+            return self.generate(Some(node));
+        }
 
-    fn gen_lval(&mut self, node: Box<Node>) -> Result<(), CompileError> {
-        match (*node).kind {
+        match node.kind {
             NodeKind::Lvar { name:_, offset, ty:_ } => {
                 // calcurate local variable address position. so, when this finishes, the top of stack is address value
                 println!("  mov rax, rbp");
                 println!("  sub rax, {}", offset);
-                println!("  push rax");
+                self.push("rax");
                 println!();
 
                 Ok(())
@@ -294,12 +1026,217 @@ impl Generator {
                 // if it is Deref, it is ok to only get the address of lhs using generate, because in Assign, the local variable indicates the address position 
                 self.generate(lhs)
             },
+            NodeKind::CompoundLiteral { init, var } => {
+                self.generate(init)?;
+                self.gen_lval(var.unwrap())
+            },
+            NodeKind::FuncRef { name, ty:_ } | NodeKind::Gvar { name, ty:_ } => {
+                println!("  lea rax, {}[rip]", name);
+                self.push("rax");
+                println!();
+
+                Ok(())
+            },
+            NodeKind::Member { lhs, name:_, offset, ty:_ } => {
+                // the address of struct + the offset of member
+                if let Some(lhs_node) = lhs {
+                    self.gen_lval(lhs_node)?;
+                };
+                self.pop("rax");
+                println!("  add rax, {}", offset);
+                self.push("rax");
+                println!();
+
+                Ok(())
+            },
             _ => {
                 Err(CompileError::new(&["the left value of assign is not a variable."]))
             }
         }
 
     }
-}
 
 
+    // load the value from the address in rax to rax
+    fn load(ty: &Ty) {
+        match ty {
+            // struct/union/array/function can't be loaded on register, so the address itself is used as the value
+            Ty::Array { .. } | Ty::Func { .. } => {},
+            _ if ty.is_aggregate() => {},
+            // unsigned integer is zero extended (writing eax clears the upper bits of rax)
+            Ty::Float | Ty::UInt => println!("  mov eax, dword ptr [rax]"),
+            Ty::UChar => println!("  movzx eax, byte ptr [rax]"),
+            _ if ty.size() == 1 => println!("  movsx rax, byte ptr [rax]"),
+            _ if ty.size() == 4 => println!("  movsxd rax, dword ptr [rax]"),
+            _ => println!("  mov rax, [rax]"),
+        }
+    }
+
+    // convert the value in rax from the type to the type
+    fn cast(from: &Ty, to: &Ty) {
+        match (from, to) {
+            (Ty::Float, Ty::Float) | (Ty::Double, Ty::Double) => {},
+            (Ty::Float, Ty::Double) => {
+                println!("  movq xmm0, rax");
+                println!("  cvtss2sd xmm0, xmm0");
+                println!("  movq rax, xmm0");
+            },
+            (Ty::Double, Ty::Float) => {
+                println!("  movq xmm0, rax");
+                println!("  cvtsd2ss xmm0, xmm0");
+                println!("  movq rax, xmm0");
+            },
+            // integer is extended to 64 bits, so it can be converted as it is
+            (_, Ty::Float) | (_, Ty::Double) => {
+                let suffix = if matches!(to, Ty::Float) { "ss" } else { "sd" };
+                println!("  cvtsi2{} xmm0, rax", suffix);
+                println!("  movq rax, xmm0");
+            },
+            // truncated toward zero
+            (Ty::Float, _) | (Ty::Double, _) => {
+                let suffix = if matches!(from, Ty::Float) { "ss" } else { "sd" };
+                println!("  movq xmm0, rax");
+                println!("  cvtt{}2si rax, xmm0", suffix);
+                Self::extend_integer(to);
+            },
+            _ => Self::extend_integer(to),
+        }
+    }
+
+    // integer is kept sign extended to 64 bits (unsigned one is zero extended)
+    fn extend_integer(ty: &Ty) {
+        match ty {
+            Ty::Char => println!("  movsx rax, al"),
+            Ty::Int | Ty::Enum => println!("  movsxd rax, eax"),
+            Ty::UChar => println!("  movzx eax, al"),
+            Ty::UInt => println!("  mov eax, eax"),
+            _ => {},
+        }
+    }
+
+    // unsigned integer wraps around, so the bits over it are dropped (the overflow of signed one is undefined)
+    fn wrap_unsigned(ty: &Ty) {
+        if ty.is_unsigned() {
+            Self::extend_integer(ty);
+        }
+    }
+
+    // divide rax by rdi, the quotient is in rax and the remainder is in rdx
+    fn gen_div(ty: &Ty) {
+        match ty.is_unsigned() {
+            true => {
+                println!("  mov edx, 0");
+                println!("  div rdi");
+            },
+            false => {
+                println!("  cqo");
+                println!("  idiv rdi");
+            },
+        }
+    }
+
+    // compare the value in rax with 0 (ZF is set if it is false), and floating point number is true if it is not 0.0 or -0.0, or NaN
+    fn cmp_zero(ty: &Ty) {
+        if ty.is_flonum() {
+            let suffix = if matches!(ty, Ty::Float) { "ss" } else { "sd" };
+            println!("  movq xmm0, rax");
+            println!("  xorps xmm1, xmm1");
+            println!("  ucomi{} xmm0, xmm1", suffix);
+            println!("  setne al");
+            println!("  setp dl");
+            println!("  or al, dl");
+            println!("  movzb rax, al");
+        }
+        println!("  cmp rax, 0");
+    }
+
+    // store the value in rdi to the address in rax
+    fn store(ty: &Ty) {
+        match ty {
+            // rdi is the address of source struct/union
+            _ if ty.is_aggregate() => Self::copy_aggregate(ty, 0),
+            _ if ty.size() == 1 => println!("  mov [rax], dil"),
+            _ if ty.size() == 4 => println!("  mov [rax], edi"),
+            _ => println!("  mov [rax], rdi"),
+        }
+    }
+
+    // load size (up to 8) bytes from [rdi+offset] to the register, byte by byte not to read over the end of struct/union
+    fn load_eightbyte(register: &str, register8: &str, offset: i32, size: i32) {
+        if size == 8 {
+            println!("  mov {}, [rdi+{}]", register, offset);
+            return;
+        }
+        println!("  mov {}, 0", register);
+        for i in (0..size).rev() {
+            println!("  shl {}, 8", register);
+            println!("  mov {}, [rdi+{}]", register8, offset + i);
+        }
+    }
+
+    // load the eightbyte of floating point numbers from [rdi+offset] to the vector register (size is 4 for a float, 8 for two floats or a double)
+    fn load_fp(reg: usize, offset: i32, size: i32) {
+        match size {
+            4 => println!("  movss xmm{}, [rdi+{}]", reg, offset),
+            _ => println!("  movsd xmm{}, [rdi+{}]", reg, offset),
+        }
+    }
+
+    // store the eightbyte of floating point numbers in the vector register to [base+offset]
+    fn store_fp(base: &str, reg: usize, offset: i32, size: i32) {
+        match size {
+            4 => println!("  movss [{}{:+}], xmm{}", base, offset, reg),
+            _ => println!("  movsd [{}{:+}], xmm{}", base, offset, reg),
+        }
+    }
+
+    // store size (up to 8) bytes of the register to [base+offset], byte by byte not to write over the end of struct/union
+    // (the register is broken)
+    fn store_eightbyte(base: &str, register: &str, register8: &str, offset: i32, size: i32) {
+        if size == 8 {
+            println!("  mov [{}+{}], {}", base, offset, register);
+            return;
+        }
+        for i in 0..size {
+            println!("  mov [{}+{}], {}", base, offset + i, register8);
+            println!("  shr {}, 8", register);
+        }
+    }
+
+    // copy struct/union from [rdi+offset] to [rax+offset]
+    fn copy_aggregate(ty: &Ty, offset: i32) {
+        match ty.unqualified() {
+            // members of union overlap, so copy the whole storage
+            Ty::Union { .. } => Self::copy_bytes(ty.size(), offset),
+            // struct is copied member by member
+            _ => {
+                for member in ty.members() {
+                    match member.ty.is_aggregate() {
+                        true => Self::copy_aggregate(&member.ty, offset + member.offset),
+                        false => Self::copy_bytes(member.ty.size(), offset + member.offset),
+                    }
+                }
+            },
+        }
+    }
+
+    // copy size bytes from [rdi+offset] to [rax+offset], using r8 as scratch
+    fn copy_bytes(size: i32, offset: i32) {
+        let mut i = 0;
+        while i + 8 <= size {
+            println!("  mov r8, [rdi+{}]", offset + i);
+            println!("  mov [rax+{}], r8", offset + i);
+            i += 8;
+        }
+        while i + 4 <= size {
+            println!("  mov r8d, [rdi+{}]", offset + i);
+            println!("  mov [rax+{}], r8d", offset + i);
+            i += 4;
+        }
+        while i < size {
+            println!("  mov r8b, [rdi+{}]", offset + i);
+            println!("  mov [rax+{}], r8b", offset + i);
+            i += 1;
+        }
+    }
+}
@@ -1,5 +1,6 @@
 mod cc_util;
 mod tokeniser;
+mod ty;
 mod parser;
 mod generator;
 
@@ -1,6 +1,5 @@
 use std::env;
 
-use tvcc;
 
 mod cc_util;
 use cc_util::CompileError;
@@ -31,8 +30,8 @@ fn get_program(mut args: env::Args) -> Result<String, CompileError> {
 
     args.next();
 
-    return match args.next() {
+    match args.next() {
         Some(arg) => Ok(arg),
         None => Err(CompileError::new(&["Didn't get a program"])),
-    };
+    }
 }
@@ -1,9 +1,10 @@
 use crate::tokeniser::{Token, TokenListIterator, TokenList, TokenKind};
 
+use crate::ty::{Ty, Qualifiers, align_to, arg_locs};
+
 use crate::cc_util::CompileError;
 
-use std::collections::HashMap;
-use std::str::FromStr;
+use std::collections::{HashMap, HashSet};
 
 
 #[derive(Debug)]
@@ -14,38 +15,57 @@ pub struct Node {
 
 impl Node {
 
-    fn ty(&mut self) -> &mut Ty {
+    pub fn ty(&mut self) -> &mut Ty {
+        // the value has no qualifiers (e.g. the value of "const int" variable is int)
+        self.qualified_ty().unqualified_mut()
+    }
+
+    // the type of lvalue including qualifiers (e.g. to check the assignment to const variable)
+    pub fn qualified_ty(&mut self) -> &mut Ty {
         // for lazy evaluation, cache in ty property (Option)
         self.ty.get_or_insert_with(|| {
 
             match &mut self.kind {
-                NodeKind::Add {lhs, .. } | NodeKind::Sub {lhs, .. }  | NodeKind::Mul {lhs, .. }  | NodeKind::Div {lhs, .. }  | NodeKind::Assign {lhs, .. } => {
+                NodeKind::Add {lhs, .. } | NodeKind::Sub {lhs, .. } => {
+                    // extract the type of lhs (array is pointer here)
+                                        lhs.as_mut().unwrap().ty().decay()
+                },
+                NodeKind::Mul {lhs, .. }  | NodeKind::Div {lhs, .. }  | NodeKind::Assign {lhs, .. } | NodeKind::Neg {lhs} |
+                NodeKind::Mod {lhs, .. } | NodeKind::BitAnd {lhs, .. } | NodeKind::BitOr {lhs, .. } | NodeKind::BitXor {lhs, .. } |
+                NodeKind::BitNot {lhs} | NodeKind::Shl {lhs, .. } | NodeKind::Shr {lhs, .. } => {
                     // extract the type of lhs, and clone
-                    let mut bx = lhs.as_mut().unwrap();
-                    (**(&mut bx)).ty().clone()
+                                        lhs.as_mut().unwrap().ty().clone()
                 },
-                NodeKind::Eq { .. }  | NodeKind::Ne {.. }  | NodeKind::Lt {.. }  | NodeKind::Le {.. }  | NodeKind::Num {.. }  | NodeKind::FuncCall {.. } => {
+                NodeKind::Eq { .. }  | NodeKind::Ne {.. }  | NodeKind::Lt {.. }  | NodeKind::Le {.. }  | NodeKind::Num {.. } |
+                NodeKind::LogAnd { .. } | NodeKind::LogOr { .. } | NodeKind::Not { .. } => {
                     Ty::Int
                 },
-                NodeKind::Lvar { name, offset:_, ty } => {
+                NodeKind::Lvar { ty, .. } | NodeKind::Gvar { ty, .. } | NodeKind::Member { ty, .. } | NodeKind::FuncRef { ty, .. } | NodeKind::FuncCall { ty, .. } | NodeKind::Cast { ty, .. } | NodeKind::VaArg { ty, .. } | NodeKind::FNum { ty, .. } | NodeKind::Cond { ty, .. } => {
                     ty.clone()
                 },
+                NodeKind::FuncPtrCall { func, .. } => {
+                    // the return type of the function
+                    let ty = func.as_mut().unwrap().ty();
+                    ty.callee().and_then(|f| f.return_ty()).cloned().unwrap_or(Ty::Int)
+                },
+                NodeKind::LabelAddr { .. } => Ty::new_pointer(Ty::Char),
+                NodeKind::Comma { rhs, .. } | NodeKind::CompoundLiteral { var: rhs, .. } => {
+                                        rhs.as_mut().unwrap().ty().clone()
+                },
                 NodeKind::Addr { lhs } => {
-                    // extract the type of lhs, and create new pointer based on the type of lhs
-                    let mut bx = lhs.as_mut().unwrap();
-                    let ty = (**(&mut bx)).ty().clone();
+                    // extract the type of lhs, and create new pointer based on the type of lhs (e.g. "&x" of const int x is "const int *")
+                                        let ty = lhs.as_mut().unwrap().qualified_ty().clone();
                     Ty::new_pointer(ty)
                 },
                 NodeKind::Deref { lhs } => {
                     // extract the type of lhs, and clone
-                    let mut bx = lhs.as_mut().unwrap();
-                    let ty = (**(&mut bx)).ty().clone();
+                                        let ty = lhs.as_mut().unwrap().ty().clone();
 
-                    match ty {
-                        Ty::Pointer { base } => {
-                            (*base.unwrap()).clone()
-                        },
-                        _ => Ty::Int,
+                    match ty.base() {
+                        Some(base) => base.clone(),
+                        // dereferenced function is the function itself
+                        None if ty.is_func() => ty,
+                        None => Ty::Int,
                     }
                 },
                 _ => Ty::Int,
@@ -57,6 +77,7 @@ impl Node {
 }
 
 #[derive(Debug)]
+#[allow(dead_code)] // some fields (e.g. name) are kept only for debug print
 pub enum NodeKind {
     Add { lhs: Option<Box<Node>>, rhs: Option<Box<Node>> }, // +
     Sub { lhs: Option<Box<Node>>, rhs: Option<Box<Node>> }, // -
@@ -66,17 +87,60 @@ pub enum NodeKind {
     Ne { lhs: Option<Box<Node>>, rhs: Option<Box<Node>> },  // !=
     Lt { lhs: Option<Box<Node>>, rhs: Option<Box<Node>> },  // <
     Le { lhs: Option<Box<Node>>, rhs: Option<Box<Node>> },  // <=
+    Mod { lhs: Option<Box<Node>>, rhs: Option<Box<Node>> },  // %
+    BitAnd { lhs: Option<Box<Node>>, rhs: Option<Box<Node>> },  // &
+    BitOr { lhs: Option<Box<Node>>, rhs: Option<Box<Node>> },  // |
+    BitXor { lhs: Option<Box<Node>>, rhs: Option<Box<Node>> },  // ^
+    BitNot { lhs: Option<Box<Node>> },  // ~
+    Shl { lhs: Option<Box<Node>>, rhs: Option<Box<Node>> },  // <<
+    Shr { lhs: Option<Box<Node>>, rhs: Option<Box<Node>> },  // >> (logical shift for unsigned type, otherwise arithmetic shift)
+    LogAnd { lhs: Option<Box<Node>>, rhs: Option<Box<Node>> },  // &&
+    Comma { lhs: Option<Box<Node>>, rhs: Option<Box<Node>> },  // evaluate lhs and discard it, and then rhs
+    Cond { cond: Option<Box<Node>>, then: Option<Box<Node>>, els: Option<Box<Node>>, ty: Ty },  // cond ? then : els
+    LogOr { lhs: Option<Box<Node>>, rhs: Option<Box<Node>> },  // ||
+    Not { lhs: Option<Box<Node>> },  // !
     Assign { lhs: Option<Box<Node>>, rhs: Option<Box<Node>> }, // =
     Lvar { name: String, offset: i32, ty:Ty }, // local variables + name, offset
+    Gvar { name: String, ty: Ty }, // global variables (e.g. string literal) + name
     Num {value: i32 }, // integer + value
+    FNum { value: f64, ty: Ty }, // floating point number + value, float or double
+    Neg { lhs: Option<Box<Node>> }, // - (unary)
     Return { lhs: Option<Box<Node>> }, // return
+    ExprStmt { lhs: Option<Box<Node>> }, // expression statement (the value is discarded)
     If { cond: Option<Box<Node>>, then: Option<Box<Node>>, else_then: Option<Box<Node>> }, // if
     For { init: Option<Box<Node>>, cond: Option<Box<Node>>, inc: Option<Box<Node>>, then: Option<Box<Node>>}, // for or while
+    DoWhile { then: Option<Box<Node>>, cond: Option<Box<Node>> }, // do ... while
+    Break, // jump to the end of the innermost loop or switch
+    Continue, // jump to the next iteration of the innermost loop
+    Switch { cond: Option<Box<Node>>, body: Option<Box<Node>>, cases: Vec<(i32, usize)>, default: Option<usize> }, // cases are (value, label index)
+    Case { index: usize, body: Option<Box<Node>> }, // case or default label in the innermost switch
+    Label { name: String, body: Option<Box<Node>> }, // labeled statement
+    Goto { name: String },
+    GotoPtr { target: Option<Box<Node>> }, // goto *p (GNU extension)
+    LabelAddr { name: String }, // &&label (GNU extension)
     Block { body: Vec<Option<Box<Node>>> }, // block
-    FuncCall { name: String, args: Vec<Option<Box<Node>>> }, // func call
-    FuncDef { name: String, r_type: Ty, params: Vec<Option<Box<Node>>>, stack_size: i32, block: Option<Box<Node>> }, // func define
+    MemZero { offset: i32, size: i32 }, // clear local variable (before its initializer is assigned)
+    CompoundLiteral { init: Option<Box<Node>>, var: Option<Box<Node>> }, // (type){...} in function, init is the statements to initialize the unnamed variable var
+    FuncCall { name: String, args: Vec<Option<Box<Node>>>, ty: Ty, ret_buffer: Option<i32> }, // func call + return type, the offset of the buffer for returned struct/union
+    FuncPtrCall { func: Option<Box<Node>>, args: Vec<Option<Box<Node>>>, ret_buffer: Option<i32> }, // func call through function pointer
+    FuncRef { name: String, ty: Ty }, // function designator + name
+    FuncDef { name: String, r_type: Ty, params: Vec<Option<Box<Node>>>, va_area: Option<i32>, ret_ptr: Option<i32>, stack_size: i32, block: Option<Box<Node>>, is_static: bool }, // func define + the offset of register save area if variadic, the offset to save the hidden return pointer, not visible from other files if static
+    GlobalDef { name: String, ty: Ty, data: Option<Vec<u8>>, relocs: Vec<Relocation>, is_static: bool }, // global variable define + initial bytes (zero if None) and the addresses in them, not visible from other files if static
     Addr { lhs: Option<Box<Node>> }, // & (pointer)
     Deref { lhs: Option<Box<Node>> }, // * (pointer)
+    Member { lhs: Option<Box<Node>>, name: String, offset: i32, ty: Ty }, // . (struct member) + name, offset
+    Cast { lhs: Option<Box<Node>>, ty: Ty }, // type conversion (e.g. argument to the parameter type)
+    VaStart { ap: Option<Box<Node>>, gp_offset: i32, fp_offset: i32, overflow_offset: i32, area_offset: i32 }, // __builtin_va_start + the initial va_list state
+    VaArg { ap: Option<Box<Node>>, ty: Ty }, // __builtin_va_arg
+}
+
+// address in the initial value of global variable, which is resolved by the linker
+// (e.g. "int *p = &x + 1;" is x+4 at offset 0 of p)
+#[derive(Debug)]
+pub struct Relocation {
+    pub offset: usize,
+    pub label: String,
+    pub addend: i64,
 }
 
 impl NodeKind {
@@ -84,76 +148,146 @@ impl NodeKind {
         Some(Box::new(Node { kind: self, ty: None }))
     }
 
-    fn eight() -> Option<Box<Node>> {
-        NodeKind::Num { value: 8 }.wrap()
+    fn num(value: i32) -> Option<Box<Node>> {
+        NodeKind::Num { value }.wrap()
     }
 }
 
-#[derive(Debug, Clone)]
-pub enum Ty {
-    Int,
-    Pointer { base: Option<Box<Ty>> },
-}
 
-impl FromStr for Ty {
-    type Err = ();
+// struct for local variales (names are in Scope)
+// the slots of variables in a closed block are reused by the following blocks
+struct LocalVariable {
+    latest_offset: i32, // the end of variables alive now
+    max_offset: i32,    // the largest latest_offset in the function, which is the size of stack frame
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "int" => Ok(Ty::Int),
-            _ => Err(()),
+impl LocalVariable {
+    fn new() -> LocalVariable {
+        LocalVariable {
+            latest_offset: 0,
+            max_offset: 0,
         }
     }
+
+    fn add_variable(&mut self, v_type: &Ty) -> i32 {
+        self.latest_offset = align_to(self.latest_offset + v_type.size(), v_type.align());
+        self.max_offset = self.max_offset.max(self.latest_offset);
+        self.latest_offset
+    }
+}
+
+
+// ordinary identifiers share one name space, so that inner one can shadow outer one of the other kind
+#[derive(Clone)]
+enum Ident {
+    Variable(i32, Ty), // local variable and its offset, type
+    GlobalVar(String, Ty), // global variable (file scope, extern or static local) and its label, type
+    Func(Ty),       // function and its type
+    Typedef(Ty),    // typedef name and its type
+    EnumConst(i32), // enum constant and its value
+}
+
+// identifiers defined in a block
+struct Scope {
+    tags: HashMap<String, Ty>,       // struct/union/enum tags
+    idents: HashMap<String, Ident>,  // variables, typedef names and enum constants
+    frame_offset: i32,               // latest_offset of local variables when the block begins
 }
 
-impl Ty {
-    fn new_pointer(base: Ty) -> Ty {
-        Ty::Pointer {
-            base: Some(Box::new(base))
+impl Scope {
+    fn new(frame_offset: i32) -> Scope {
+        Scope {
+            tags: HashMap::new(),
+            idents: HashMap::new(),
+            frame_offset,
         }
     }
 }
 
+// function or global variable declared in the program (declaration or definition)
+struct GlobalSymbol {
+    ty: Ty,
+    is_defined: bool,
+    is_static: bool, // internal linkage, which is decided by the first declaration
+}
 
-// struct for local variales 
-struct LocalVariable {
-    latest_offset: i32,
-    variables: HashMap<String, (i32, Ty)>,
+// case labels found in a switch statement
+struct SwitchCases {
+    cases: Vec<(i32, usize)>, // (value, label index)
+    default: Option<usize>,   // label index
+    labels: usize,            // the number of labels
 }
 
-impl LocalVariable {
-    fn new() -> LocalVariable {
-        LocalVariable {
-            latest_offset: 0,
-            variables: HashMap::new(),
-        }
+// initializer of variable, which is a tree along the type
+// (array and struct/union have the children for the elements/members, scalar has the expression)
+struct Initializer {
+    ty: Ty,
+    children: Vec<Initializer>,
+    expr: Option<Box<Node>>, // None if not given (zero), or struct/union initialized by its value
+    member: usize, // the member of union to be initialized (the first one, or the one given by designator)
+}
+
+impl Initializer {
+    fn new(ty: Ty) -> Initializer {
+        let children = match ty.unqualified() {
+            // array of unknown length has no children until the elements are read
+            Ty::Array { base, len } => (0..*len).map(|_| Initializer::new((**base).clone())).collect(),
+            Ty::Struct { .. } | Ty::Union { .. } => ty.members().into_iter().map(|m| Initializer::new(m.ty)).collect(),
+            _ => Vec::new(),
+        };
+        Initializer { ty, children, expr: None, member: 0 }
     }
 
-    fn add_variable(&mut self, variale_name: String, v_type: Ty) -> bool {
-        match self.variables.entry(variale_name) {
-            std::collections::hash_map::Entry::Occupied(_entry) => false,
-            std::collections::hash_map::Entry::Vacant(entry) => {
-                self.latest_offset = self.latest_offset + 8;
-                entry.insert((self.latest_offset, v_type));
-                true
-            }
+    // the number of children initialized in order without designator (union has only one)
+    fn positional_len(&self) -> usize {
+        match self.ty.is_union() {
+            true => self.children.len().min(1),
+            false => self.children.len(),
         }
     }
 
-    fn find_variable(&mut self, variale_name: &str) -> Option<(i32, Ty)> {
-        match self.variables.get(variale_name) {
-            Some((offset, ty)) => Some((*offset, ty.clone())), // TODO although "find" method, it is need "clone". waste of memory
-            _ => None
+    // the children to be initialized and their offsets from the head of this
+    fn into_children(self) -> Vec<(Initializer, i32)> {
+        let offsets: Vec<i32> = match self.ty.base() {
+            Some(base) if self.ty.is_array() => (0..self.children.len() as i32).map(|i| i * base.size()).collect(),
+            _ => self.ty.members().iter().map(|m| m.offset).collect(),
+        };
+        let children = self.children.into_iter().zip(offsets);
+        match self.ty.is_union() {
+            true => children.skip(self.member).take(1).collect(),
+            false => children.collect(),
         }
     }
 }
 
+// storage class etc. given in declspec
+#[derive(Default)]
+struct VarAttr {
+    is_typedef: bool,
+    is_static: bool,
+    is_extern: bool,
+}
 
 
 pub struct Parser<'a> {
     token_iter: TokenListIterator<'a>,
     cur_func: String,
     local_variables: HashMap<String, LocalVariable>,
+    scopes: Vec<Scope>, // the last is the innermost block
+    symbols: HashMap<String, GlobalSymbol>, // functions and global variables with linkage, all declarations of a name must agree
+    globals: Vec<Option<Box<Node>>>, // GlobalDef, emitted after functions
+    str_count: usize, // to name string literals uniquely
+    static_count: usize, // to name compound literals at file scope and static local variables uniquely
+    va_list: Ty, // __builtin_va_list
+    loop_depth: usize, // the number of loops enclosing the current statement (for continue)
+    break_depth: usize, // the number of loops and switches enclosing the current statement (for break)
+    switches: Vec<SwitchCases>, // the last is the innermost switch
+    labels: HashSet<String>, // labels defined in the current function
+    goto_labels: Vec<String>, // labels referred by goto and &&label in the current function, checked at the end of function
+    param_decls: Vec<(Option<String>, Ty)>, // the names and types of the parameters read last, which are of the function being defined
+    tentatives: Vec<String>, // global variables declared without initializer, defined at the end unless they are defined with initializer
+    va_area: Option<i32>, // the offset of the register save area of the current function (None if it is not variadic)
+    sizeof_depth: usize, // the number of sizeof enclosing the current expression, whose operand is not evaluated
 }
 
 impl<'a> Parser<'a> {
@@ -162,10 +296,38 @@ impl<'a> Parser<'a> {
         let token_iter: TokenListIterator<'a> = token_list.iter();
         let local_variables = HashMap::new();
 
+        // the layout of va_list in System V ABI (unsigned int and void * in other compilers)
+        let va_elem = Ty::new_struct(None);
+        va_elem.define_members(vec![
+            (Some("gp_offset".to_string()), Ty::Int),
+            (Some("fp_offset".to_string()), Ty::Int),
+            (Some("overflow_arg_area".to_string()), Ty::new_pointer(Ty::Char)),
+            (Some("reg_save_area".to_string()), Ty::new_pointer(Ty::Char)),
+        ]).unwrap();
+        let va_list = Ty::new_array(va_elem, 1);
+
+        let mut global = Scope::new(0);
+        global.idents.insert("__builtin_va_list".to_string(), Ident::Typedef(va_list.clone()));
+
         Parser {
             token_iter,
             cur_func: Default::default(),
             local_variables,
+            scopes: vec![global],
+            symbols: HashMap::new(),
+            globals: Vec::new(),
+            str_count: 0,
+            static_count: 0,
+            va_list,
+            loop_depth: 0,
+            break_depth: 0,
+            switches: Vec::new(),
+            labels: HashSet::new(),
+            goto_labels: Vec::new(),
+            param_decls: Vec::new(),
+            tentatives: Vec::new(),
+            va_area: None,
+            sizeof_depth: 0,
         }
     }
 
@@ -183,41 +345,160 @@ impl<'a> Parser<'a> {
     //    }
     //}
 
+    // the variable is visible in the current block, and it can shadow the one in outer blocks
     fn cur_func_add_local_variable_by_type(&mut self, variale_name: &str, v_type: Ty) -> Result<(), CompileError> {
 
-        if self.local_variables.get_mut(&self.cur_func).unwrap().add_variable(variale_name.to_string(), v_type) {
-            Ok(())
-        } else {
-            Err(CompileError::new(&[&format!("variable: {} is already defined in {}", variale_name, &self.cur_func)]))
+        if self.scopes.last().unwrap().idents.contains_key(variale_name) {
+            return Err(CompileError::new(&[&format!("variable: {} is already defined in {}", variale_name, &self.cur_func)]));
         }
+        let offset = self.local_variables.get_mut(&self.cur_func).unwrap().add_variable(&v_type);
+        self.push_ident(variale_name.to_string(), Ident::Variable(offset, v_type))
+    }
+
+    // local variable which can't be referred by name (e.g. buffer for the returned struct), returns the offset
+    fn cur_func_add_hidden_local_variable(&mut self, v_type: Ty) -> i32 {
+        self.local_variables.get_mut(&self.cur_func).unwrap().add_variable(&v_type)
+    }
+
+    // the code is generated only for the expressions in function, but not for the operand of sizeof (e.g. "sizeof(f())" in file scope)
+    fn is_evaluated(&self) -> bool {
+        self.scopes.len() > 1 && self.sizeof_depth == 0
     }
 
     fn cur_func_local_variable_offset(&mut self, variale_name: &str) -> Result<(i32, Ty), CompileError> {
-        match self.local_variables.get_mut(&self.cur_func).unwrap().find_variable(variale_name) {
-            Some((offset, ty)) => Ok((offset, ty)),
-            None => Err(CompileError::new(&[&format!("variable: {} is not defined in {}", variale_name, &self.cur_func)]))
+        // there is no local variable outside of function (e.g. enum in file scope)
+        match self.find_ident(variale_name) {
+            Some(Ident::Variable(offset, ty)) => Ok((offset, ty)),
+            _ => Err(CompileError::new(&[&format!("variable: {} is not defined in {}", variale_name, &self.cur_func)]))
         }
     }
 
 
     fn cur_func_calculate_stack_size(&mut self) -> i32 {
         // calucuate offset and align
-        let total_offset = self.local_variables.get_mut(&self.cur_func).unwrap().latest_offset;
-        Self::align_to(total_offset, 16)
+        let total_offset = self.local_variables.get_mut(&self.cur_func).unwrap().max_offset;
+        align_to(total_offset, 16)
+    }
+
+
+    fn enter_scope(&mut self) {
+        let frame_offset = self.local_variables.get(&self.cur_func).map_or(0, |lv| lv.latest_offset);
+        self.scopes.push(Scope::new(frame_offset));
+    }
+
+    // the variables in the block are dead, so their slots can be used again
+    fn leave_scope(&mut self) {
+        let scope = self.scopes.pop().unwrap();
+        if let Some(lv) = self.local_variables.get_mut(&self.cur_func) {
+            lv.latest_offset = scope.frame_offset;
+        }
+    }
+
+    fn push_tag(&mut self, tag: String, ty: Ty) {
+        self.scopes.last_mut().unwrap().tags.insert(tag, ty);
+    }
+
+    // search from the innermost scope
+    fn find_tag(&self, tag: &str) -> Option<Ty> {
+        self.scopes.iter().rev().find_map(|scope| scope.tags.get(tag).cloned())
+    }
+
+    fn push_ident(&mut self, name: String, ident: Ident) -> Result<(), CompileError> {
+        let scope = self.scopes.last_mut().unwrap();
+        if scope.idents.contains_key(&name) {
+            return Err(CompileError::new(&[&format!("identifier: {} is already defined in {}", name, &self.cur_func)]));
+        }
+        scope.idents.insert(name, ident);
+        Ok(())
+    }
+
+    // search from the innermost scope
+    fn find_ident(&self, name: &str) -> Option<Ident> {
+        self.scopes.iter().rev().find_map(|scope| scope.idents.get(name).cloned())
+    }
+
+    // register function or global variable to the symbol table, returns whether it has internal linkage
+    // (redeclaration is allowed if the type is the same, but redefinition is not)
+    fn declare_global(&mut self, name: &str, ty: &Ty, is_definition: bool, is_static: bool) -> Result<bool, CompileError> {
+        let Some(symbol) = self.symbols.get_mut(name) else {
+            self.symbols.insert(name.to_string(), GlobalSymbol { ty: ty.clone(), is_defined: is_definition, is_static });
+            return Ok(is_static);
+        };
+
+        if !symbol.ty.is_compatible(ty) {
+            return Err(CompileError::new(&[&format!("{} is declared with conflicting types", name)]));
+        }
+        if symbol.is_defined && is_definition {
+            return Err(CompileError::new(&[&format!("{} is already defined", name)]));
+        }
+        // the declaration without "static" follows the previous one (e.g. "static int f(); int f() { ... }")
+        if is_static && !symbol.is_static {
+            return Err(CompileError::new(&[&format!("static declaration of {} follows non-static declaration", name)]));
+        }
+        symbol.is_defined = symbol.is_defined || is_definition;
+
+        // array of unknown length and unprototyped function are completed by the later declaration
+        if !symbol.ty.is_complete() || !symbol.ty.is_prototyped() {
+            symbol.ty = ty.clone();
+        }
+        Ok(symbol.is_static)
+    }
+
+    // register function to the symbol table and the current scope, returns whether it has internal linkage
+    fn declare_func(&mut self, name: &str, ty: Ty, is_definition: bool, is_static: bool) -> Result<bool, CompileError> {
+        let is_static = self.declare_global(name, &ty, is_definition, is_static)?;
+        let ident = Ident::Func(self.symbols[name].ty.clone());
+
+        // the same declaration can be repeated in a scope (e.g. prototype and definition)
+        let scope = self.scopes.last_mut().unwrap();
+        if let Some(Ident::Func(_)) = scope.idents.get(name) {
+            scope.idents.insert(name.to_string(), ident);
+            return Ok(is_static);
+        }
+        self.push_ident(name.to_string(), ident)?;
+        Ok(is_static)
+    }
+
+    // register global variable with linkage (file scope or extern) to the symbol table and the current scope,
+    // the label is the name itself, returns whether it has internal linkage
+    fn declare_global_var(&mut self, name: &str, ty: Ty, is_definition: bool, is_static: bool) -> Result<bool, CompileError> {
+        let is_static = self.declare_global(name, &ty, is_definition, is_static)?;
+        let ident = Ident::GlobalVar(name.to_string(), self.symbols[name].ty.clone());
+
+        // the same declaration can be repeated in a scope (e.g. "extern int x; int x = 1;")
+        let scope = self.scopes.last_mut().unwrap();
+        if let Some(Ident::GlobalVar(..)) = scope.idents.get(name) {
+            scope.idents.insert(name.to_string(), ident);
+            return Ok(is_static);
+        }
+        self.push_ident(name.to_string(), ident)?;
+        Ok(is_static)
     }
 
-    fn align_to(n: i32, align: i32) -> i32 {
-        (n + align - 1) / align * align
+    fn find_typedef(&self, name: &str) -> Option<Ty> {
+        match self.find_ident(name) {
+            Some(Ident::Typedef(ty)) => Some(ty),
+            _ => None,
+        }
+    }
+
+    // the token starts type (keyword or typedef name)
+    fn is_typename(&self, token: &Token) -> bool {
+        match &token.kind {
+            TokenKind::Type(_) => true,
+            TokenKind::Ident(name) => self.find_typedef(name).is_some(),
+            _ => false,
+        }
     }
 
 
 
     fn cur_token(&self) -> &Token {
-        &self.token_iter.current().unwrap()
+        self.token_iter.current().unwrap()
     }
 
     fn next_token(&mut self) -> &'a Token {
-        &self.token_iter.next().unwrap()
+        self.token_iter.next().unwrap()
     }
 
 
@@ -229,55 +510,161 @@ impl<'a> Parser<'a> {
         let mut functions: Vec<Option<Box<Node>>> = Vec::new();
 
         while !self.cur_token().at_eof() {
-            functions.push(self.function()?);
+            if let TokenKind::StaticAssert = self.cur_token().kind {
+                self.static_assert()?;
+                continue;
+            }
+
+            // return type
+            let mut attr = VarAttr::default();
+            let r_type: Ty = self.declspec(Some(&mut attr))?;
+
+            if attr.is_typedef {
+                self.parse_typedef(r_type)?;
+                continue;
+            }
+
+            // only type declaration (e.g. "struct foo { int a; };")
+            if self.cur_token().expect_symbol(";").is_ok() {
+                let _ = &self.next_token();
+                continue;
+            }
+
+            // function definition is the declarator of function type followed by its body
+            // (e.g. "int (*get())(int) { ... }" returns function pointer)
+            let (name, ty) = self.declarator(r_type.clone())?;
+            if ty.is_func() && self.cur_token().expect_symbol("{").is_ok() {
+                functions.push(self.function(name, ty, &attr)?);
+                continue;
+            }
+
+            self.global_variable(name, ty, r_type, &attr)?;
+        }
+
+        // tentative definitions of a variable are one definition with zero (e.g. "int x; int x;")
+        for name in std::mem::take(&mut self.tentatives) {
+            let symbol = &self.symbols[&name];
+            if symbol.is_defined {
+                continue;
+            }
+            if !symbol.ty.is_complete() {
+                return Err(CompileError::new(&[&format!("variable: {} has incomplete type", name)]));
+            }
+            self.globals.push(NodeKind::GlobalDef { name, ty: symbol.ty.clone(), data: None, relocs: Vec::new(), is_static: symbol.is_static }.wrap());
         }
 
+        functions.append(&mut self.globals);
         Ok(functions)
     }
 
-    fn function(&mut self) -> Result<Option<Box<Node>>, CompileError> {
-        // return type
-        let r_type: Ty = self.declspec()?;
+    // global_variable = declarator ("=" initializer)? ("," declarator ("=" initializer)?)* ";"
+    // the first declarator is already read to know it is not function definition
+    fn global_variable(&mut self, v_name: String, v_ty: Ty, base_type: Ty, attr: &VarAttr) -> Result<(), CompileError> {
+        self.global_declaration(v_name, v_ty, attr)?;
+        while self.cur_token().expect_symbol(",").is_ok() {
+            let _ = &self.next_token();
+            let (v_name, v_ty) = self.declarator(base_type.clone())?;
+            self.global_declaration(v_name, v_ty, attr)?;
+        }
+        self.stmt_expect_symbol(";")
+    }
+
+    // a declarator of global variable or function declaration, the initial values are computed at compile time
+    fn global_declaration(&mut self, v_name: String, v_ty: Ty, attr: &VarAttr) -> Result<(), CompileError> {
 
-        // func name
-        let name = self.cur_token().expect_ident()?.to_string();
+        // function declaration (e.g. "int x, add(int, int);")
+        if v_ty.is_func() {
+            self.declare_func(&v_name, v_ty, false, attr.is_static)?;
+            return Ok(());
+        }
 
-        self.set_cur_func(name.clone());
+        // extern declaration refers to the variable defined elsewhere, unless it has initializer
+        if attr.is_extern && self.cur_token().expect_symbol("=").is_err() {
+            self.declare_global_var(&v_name, v_ty, false, false)?;
+            return Ok(());
+        }
+
+        // variable without storage class has external linkage unlike function (e.g. "static int x; int x;" conflicts)
+        if !attr.is_static && !attr.is_extern && self.symbols.get(&v_name).is_some_and(|symbol| symbol.is_static) {
+            return Err(CompileError::new(&[&format!("non-static declaration of {} follows static declaration", v_name)]));
+        }
+
+        // the declaration without initializer is tentative definition, which can be repeated (e.g. "int x; int x = 1;")
+        if self.cur_token().expect_symbol("=").is_err() {
+            self.declare_global_var(&v_name, v_ty, false, attr.is_static)?;
+            if !self.tentatives.contains(&v_name) {
+                self.tentatives.push(v_name);
+            }
+            return Ok(());
+        }
 
         let _ = &self.next_token();
+        let init = self.var_initializer(v_ty)?;
+        let v_ty = init.ty.clone();
+        if !v_ty.is_complete() {
+            return Err(CompileError::new(&[&format!("variable: {} has incomplete type", v_name)]));
+        }
+        let (data, relocs) = self.global_init(init)?;
 
-        // argument
-        self.stmt_expect_symbol("(")?;
+        let is_static = self.declare_global_var(&v_name, v_ty.clone(), true, attr.is_static)?;
+        self.globals.push(NodeKind::GlobalDef { name: v_name, ty: v_ty, data: Some(data), relocs, is_static }.wrap());
+        Ok(())
+    }
 
-        let mut params: Vec<Option<Box<Node>>> = Vec::new();
+    // function = declspec declarator "{" compound_stmt
+    // the parameters of the function type are read last by the declarator, so their names are in param_decls
+    fn function(&mut self, name: String, func_ty: Ty, attr: &VarAttr) -> Result<Option<Box<Node>>, CompileError> {
 
-        // func args
-        while let Err(_) = self.cur_token().expect_symbol(")") {
+        self.set_cur_func(name.clone());
+        let r_type = func_ty.return_ty().unwrap().clone();
+        let is_variadic = func_ty.is_variadic();
+        let param_decls = std::mem::take(&mut self.param_decls);
 
-            if let Ok(_) = self.cur_token().expect_symbol(",") {
-                let _ = &self.next_token();
-            }
+        // register the function before the body, so that it can be referred recursively
+        let is_static = self.declare_func(&name, func_ty, true, attr.is_static)?;
 
-            // identify local variable
-            let v_type: Ty = self.declspec()?;
+        self.enter_scope();
 
-            // define local variable
-            let (v_name, v_ty) = self.declarator(v_type)?;
+        // define local variable
+        let mut params: Vec<Option<Box<Node>>> = Vec::new();
+        for (v_name, v_ty) in param_decls {
+            let Some(v_name) = v_name else {
+                return Err(CompileError::new(&[&format!("parameter name is omitted in {}", &name)]));
+            };
+            self.cur_func_add_local_variable_by_type(&v_name, v_ty.clone())?;
             let (offset, _) = self.cur_func_local_variable_offset(&v_name)?; // definitely success because it is just after add variable
-            params.push(NodeKind::Lvar{ name: v_name, offset, ty: v_ty }.wrap());
-
+            params.push(NodeKind::Lvar{ name: v_name, offset, ty: v_ty.unqualified().clone() }.wrap());
         }
 
-        let _ = &self.next_token(); // skip ")"
+        // struct/union of MEMORY class is returned to the buffer given by caller in rdi, so save the pointer
+        let ret_ptr = match r_type.is_memory_class() {
+            true => Some(self.cur_func_add_hidden_local_variable(Ty::new_pointer(r_type.clone()))),
+            false => None,
+        };
+
+        // variadic function saves argument registers, so that va_arg can read them (6 general purpose, 8 vector registers)
+        self.va_area = match is_variadic {
+            true => Some(self.cur_func_add_hidden_local_variable(Ty::new_array(Ty::Char, 6 * 8 + 8 * 16))),
+            false => None,
+        };
+        let va_area = self.va_area;
 
         // block
+        self.labels.clear();
+        self.goto_labels.clear();
         self.stmt_expect_symbol("{")?;
-        let block = self.compound_stmt()?;
+        let block = self.block_items()?;
+        self.leave_scope();
+
+        // labels have function scope, so goto can jump forward
+        if let Some(label) = self.goto_labels.iter().find(|l| !self.labels.contains(*l)) {
+            return Err(CompileError::new(&[&format!("label: {} is used but not defined in {}", label, &name)]));
+        }
 
         let stack_size = self.cur_func_calculate_stack_size();
 
-        let node = NodeKind::FuncDef { name, r_type, params, stack_size, block }.wrap();
-        return Ok(node);
+        let node = NodeKind::FuncDef { name, r_type, params, va_area, ret_ptr, stack_size, block, is_static }.wrap();
+        Ok(node)
     }
 
 
@@ -285,10 +672,26 @@ impl<'a> Parser<'a> {
     // compound_stmt = (declaration | stmt)* "}"
     fn compound_stmt(&mut self) -> Result<Option<Box<Node>>, CompileError> {
 
+        // variables, typedef names and tags defined in this block are not visible from outside
+        self.enter_scope();
+        let node = self.block_items();
+        self.leave_scope();
+        node
+    }
+
+    // the items of block in the current scope (the body of function shares the scope with parameters)
+    fn block_items(&mut self) -> Result<Option<Box<Node>>, CompileError> {
+
         let mut stmts: Vec<Option<Box<Node>>> = Vec::new();
-        while let Err(_) = self.cur_token().expect_symbol("}") {
+        while self.cur_token().expect_symbol("}").is_err() {
+
+            if let TokenKind::StaticAssert = self.cur_token().kind {
+                self.static_assert()?;
+                continue;
+            }
 
-            if let Token { kind: TokenKind::Type(_), .. } = self.cur_token() {
+            // typedef name is an identifier token, so need to check the scopes to know it is a type
+            if self.is_typename(self.cur_token()) {
                 stmts.push(self.declaration()?);
             } else {
                 stmts.push(self.stmt()?);
@@ -297,149 +700,909 @@ impl<'a> Parser<'a> {
         self.stmt_expect_symbol("}")?;
 
         let node = NodeKind::Block { body: stmts, }.wrap();
-        return Ok(node);
+        Ok(node)
     }
 
 
 
     fn declaration(&mut self) -> Result<Option<Box<Node>>, CompileError> {
-        let base_type: Ty = self.declspec()?;
+        let mut attr = VarAttr::default();
+        let base_type: Ty = self.declspec(Some(&mut attr))?;
+
+        if attr.is_typedef {
+            self.parse_typedef(base_type)?;
+            return Ok(NodeKind::Block { body: Vec::new(), }.wrap());
+        }
 
         let mut assigns: Vec<Option<Box<Node>>> = Vec::new();
-        while let Err(_) = self.cur_token().expect_symbol(";") {
+        while self.cur_token().expect_symbol(";").is_err() {
 
-            if let Ok(_) = self.cur_token().expect_symbol(",") {
+            if self.cur_token().expect_symbol(",").is_ok() {
                 let _ = &self.next_token();
             }
 
             let (v_name, v_ty) = self.declarator(base_type.clone())?;
 
+            // function declaration in block (e.g. "int add(int, int);")
+            if v_ty.is_func() {
+                if attr.is_static {
+                    return Err(CompileError::new(&[&format!("function: {} is declared static in block in {}", v_name, &self.cur_func)]));
+                }
+                self.declare_func(&v_name, v_ty, false, false)?;
+                continue;
+            }
+
+            // extern variable in block refers to the global one (e.g. "extern int x;")
+            if attr.is_extern {
+                if self.cur_token().expect_symbol("=").is_ok() {
+                    return Err(CompileError::new(&[&format!("extern variable: {} has initializer in {}", v_name, &self.cur_func)]));
+                }
+                self.declare_global_var(&v_name, v_ty, false, false)?;
+                continue;
+            }
+
+            if attr.is_static {
+                self.static_local_variable(v_name, v_ty)?;
+                continue;
+            }
+
+            // the variable is visible in its initializer, but array of unknown length is added after the length is known
+            if v_ty.is_complete() {
+                self.cur_func_add_local_variable_by_type(&v_name, v_ty.clone())?;
+            }
+
             let Ok(_) = self.cur_token().expect_symbol("=") else {
+                if !v_ty.is_complete() {
+                    return Err(CompileError::new(&[&format!("variable: {} has incomplete type in {}", v_name, &self.cur_func)]));
+                }
                 continue;
             };
 
             let _ = &self.next_token();
 
-            let (offset, _) = self.cur_func_local_variable_offset(&v_name)?; // definitely success because it is just after add variable
-            assigns.push(NodeKind::Assign { lhs: NodeKind::Lvar { name: v_name, offset, ty:v_ty }.wrap(), rhs: self.expr()?, }.wrap());
-
+            let init = self.var_initializer(v_ty.clone())?;
+            if !v_ty.is_complete() {
+                if !init.ty.is_complete() {
+                    return Err(CompileError::new(&[&format!("variable: {} has incomplete type in {}", v_name, &self.cur_func)]));
+                }
+                self.cur_func_add_local_variable_by_type(&v_name, init.ty.clone())?;
+            }
 
+            let (offset, _) = self.cur_func_local_variable_offset(&v_name)?; // definitely success because it is just after add variable
+            assigns.push(Self::local_initializer(&v_name, offset, init));
         }
 
         let node = NodeKind::Block { body: assigns, }.wrap();
-        return Ok(node);
+        Ok(node)
     }
 
-    fn declspec(&mut self) -> Result<Ty, CompileError> {
-        let v_type_name = self.cur_token().expect_type()?.to_string();
+    // static local variable lives in .data with unique label (e.g. "main.x.0"), and it is initialized only once at compile time
+    fn static_local_variable(&mut self, v_name: String, v_ty: Ty) -> Result<(), CompileError> {
+        let label = format!("{}.{}.{}", &self.cur_func, v_name, self.static_count);
+        self.static_count += 1;
 
-        let _ = &self.next_token();
+        let (v_ty, data, relocs) = match self.cur_token().expect_symbol("=") {
+            Ok(_) => {
+                let _ = &self.next_token();
+                let init = self.var_initializer(v_ty)?;
+                let v_ty = init.ty.clone();
+                if !v_ty.is_complete() {
+                    return Err(CompileError::new(&[&format!("variable: {} has incomplete type in {}", v_name, &self.cur_func)]));
+                }
+                let (data, relocs) = self.global_init(init)?;
+                (v_ty, Some(data), relocs)
+            },
+            Err(_) => (v_ty, None, Vec::new()),
+        };
+
+        if !v_ty.is_complete() {
+            return Err(CompileError::new(&[&format!("variable: {} has incomplete type in {}", v_name, &self.cur_func)]));
+        }
+        self.push_ident(v_name, Ident::GlobalVar(label.clone(), v_ty.clone()))?;
+        self.globals.push(NodeKind::GlobalDef { name: label, ty: v_ty, data, relocs, is_static: true }.wrap());
+        Ok(())
+    }
+
+    // the initializer of variable, and the type is completed by it (e.g. "int a[] = {1, 2};" is int [2])
+    fn var_initializer(&mut self, ty: Ty) -> Result<Initializer, CompileError> {
+        let mut init = Initializer::new(ty);
 
-        match v_type_name.parse::<Ty>() {
-            Ok(v_type) => Ok(v_type),
-            Err(_) => {
-                Err(CompileError::new(&[&format!("type: {} is not defined in {}", v_type_name, &self.cur_func)]))
+        // braces can be omitted only for inner array (e.g. "int a[2][2] = {1, 2, 3, 4};")
+        let token = self.cur_token();
+        if init.ty.is_array() && token.expect_symbol("{").is_err() && !matches!(token.kind, TokenKind::Str(_)) {
+            return Err(token.error("array must be initialized by a brace-enclosed list"));
+        }
+
+        // struct/union without braces must be initialized by the value of the same type (e.g. "struct P q = p;")
+        if init.ty.is_aggregate() && token.expect_symbol("{").is_err() {
+            let mut expr = self.assign()?;
+            let from = expr.as_mut().unwrap().ty().clone();
+            if !from.is_compatible(&init.ty) {
+                return Err(CompileError::new(&[&format!("invalid initializer for {} from {} in {}", init.ty, from, &self.cur_func)]));
             }
+            init.expr = expr;
+            return Ok(init);
         }
 
+        self.initializer(&mut init)?;
+        Ok(init)
     }
 
-    fn declarator(&mut self, base_type: Ty) -> Result<(String, Ty), CompileError> {
-        // while "*" continues, creates Ty including original type
-        let mut ty = base_type;
-        while let Ok(_) = self.cur_token().expect_symbol("*") {
-            ty = Ty::new_pointer(ty);
+    // initializer = string_literal | "{" (designation? initializer ("," designation? initializer)* ","?)? "}" | assign
+    // the braces of inner array/struct/union can be omitted, then the elements are read as many as it has
+    fn initializer(&mut self, init: &mut Initializer) -> Result<(), CompileError> {
+
+        // char array is initialized by the characters of string literal (e.g. "char s[] = "abc";")
+        if let TokenKind::Str(bytes) = &self.cur_token().kind && init.ty.is_array() && init.ty.base().is_some_and(|base| matches!(base.unqualified(), Ty::Char | Ty::UChar)) {
+            let mut data = bytes.clone();
+            data.push(0);
             let _ = &self.next_token();
+            self.string_initializer(init, data);
+            return Ok(());
         }
 
-        let v_name = self.cur_token().expect_ident()?.to_string();
+        if self.cur_token().expect_symbol("{").is_ok() {
+            // braces around scalar are allowed (e.g. "int x = {3};")
+            if !init.ty.is_array() && !init.ty.is_aggregate() {
+                let _ = &self.next_token();
+                self.initializer(init)?;
+                return self.initializer_end();
+            }
+            return self.brace_initializer(init);
+        }
 
-        // define local variable (TODO here, deep copy ty instance, it is waste of memory, but need re-think the structure local variables)
-        let _ = self.cur_func_add_local_variable_by_type(&v_name, ty.clone())?;
+        // string literal is not the value of struct/union, but of the char array in it (e.g. "struct { char s[4]; } x[] = {"abc"};")
+        if init.ty.is_array() || init.ty.is_aggregate() && matches!(self.cur_token().kind, TokenKind::Str(_)) {
+            return self.elided_initializer(init, None);
+        }
 
-        let _ = &self.next_token();
-        return Ok((v_name, ty)); 
+        let expr = self.assign()?;
+        self.expr_initializer(init, expr)
     }
 
+    // the expression is the value of struct/union if the type is the same (e.g. "{p, q}" of "struct P a[2]"),
+    // otherwise it is of the first element whose braces are omitted (e.g. "{1, 2}" of "struct { int a[2]; }")
+    fn expr_initializer(&mut self, init: &mut Initializer, mut expr: Option<Box<Node>>) -> Result<(), CompileError> {
+        let from = expr.as_mut().unwrap().ty().clone();
+        if init.ty.is_aggregate() && from.is_compatible(&init.ty) {
+            init.expr = expr;
+            return Ok(());
+        }
+        if init.ty.is_array() || init.ty.is_aggregate() {
+            return self.elided_initializer(init, Some(expr));
+        }
 
+        // the value is converted as if it is assigned (e.g. "int x = s;" for struct s is invalid)
+        self.check_pointer_conversion(&from, &init.ty)?;
+        init.expr = match self.new_cast_for_assign(expr, init.ty.unqualified()) {
+            Some(expr) => expr,
+            None => return Err(CompileError::new(&[&format!("incompatible types when initializing {} from {} in {}", init.ty, from.decay(), &self.cur_func)])),
+        };
+        Ok(())
+    }
 
-    fn stmt(&mut self) -> Result<Option<Box<Node>>, CompileError> {
-
-
-        let cur = self.cur_token();
-
-        match cur {
-            // "return" expr ";"
-            Token { kind: TokenKind::Return, .. } => {
-                let _ = &self.next_token();
-                let node = NodeKind::Return { lhs: self.expr()?, }.wrap();
-                self.stmt_expect_symbol(";")?;
-                return Ok(node);
+    // the null character is not stored if the array is just the length of characters (e.g. "char s[3] = "abc";")
+    fn string_initializer(&self, init: &mut Initializer, data: Vec<u8>) {
+        match init.ty.array_len() {
+            // the element keeps the qualifiers (e.g. "const char s[] = "abc";" is const)
+            Some(len) if len < 0 => *init = Initializer::new(Ty::new_array(init.ty.base().unwrap().clone(), data.len() as i32)),
+            Some(len) if len < data.len() as i32 - 1 => {
+                eprintln!("warning: initializer-string for char array is too long in {}", &self.cur_func);
             },
-            // "if" "(" expr ")" stmt ("else" stmt)?
-            Token { kind: TokenKind::If, .. } => {
+            _ => {},
+        }
 
+        for (child, b) in init.children.iter_mut().zip(data) {
+            child.expr = NodeKind::num(b as i8 as i32);
+        }
+    }
 
-                let _ = &self.next_token();
-                self.stmt_expect_symbol("(")?;
-                // cond
-                let cond = self.expr()?;
+    // "{" initializer ("," initializer)* ","? "}" for array/struct/union
+    // the element after designated one is the next of it (e.g. "{[2] = 1, 2}" is "{0, 0, 1, 2}")
+    fn brace_initializer(&mut self, init: &mut Initializer) -> Result<(), CompileError> {
+        let _ = &self.next_token(); // skip "{"
 
-                self.stmt_expect_symbol(")")?;
+        // array of unknown length has as many elements as given
+        let is_flexible = init.ty.array_len().is_some_and(|len| len < 0);
 
-                // then
-                let then = self.stmt()?;
+        let mut i = 0;
+        let mut is_first = true;
+        while !self.is_initializer_end() {
+            if !is_first {
+                self.stmt_expect_symbol(",")?;
+            }
+            is_first = false;
 
-                // else_then
-                match self.cur_token().at_else() {
-                    true => {
-                        let _ = &self.next_token();
-                        let node = NodeKind::If { cond, then, else_then: self.stmt()? }.wrap();
-                        return Ok(node);
-                    },
-                    false => {
-                        let node = NodeKind::If { cond, then, else_then: None }.wrap();
-                        return Ok(node);
-                    }
-                }
+            if self.is_designator() {
+                i = self.designation(init)? + 1;
+                continue;
+            }
 
-            },
-            // "for" "(" expr? ";" expr? ";" expr? ")" stmt
-            Token { kind: TokenKind::For, .. } => {
+            if is_flexible && i == init.children.len() {
+                init.children.push(Initializer::new(init.ty.base().unwrap().clone()));
+            }
 
-                let _ = &self.next_token();
-                self.stmt_expect_symbol("(")?;
+            match i < init.positional_len() {
+                true => {
+                    init.member = i;
+                    self.initializer(&mut init.children[i])?;
+                },
+                false => self.skip_excess_initializer()?,
+            }
+            i += 1;
+        }
 
-                // init
-                let init = match self.cur_token().expect_symbol(";") {
-                    Ok(_n) => None,
-                    Err(_) => self.expr()?,
-                };
+        if is_flexible {
+            init.ty = Ty::new_array(init.ty.base().unwrap().clone(), init.children.len() as i32);
+        }
+        self.initializer_end()
+    }
 
-                // cond
-                let _ = &self.next_token();
-                let cond = match self.cur_token().expect_symbol(";") {
-                    Ok(_n) => None,
-                    Err(_) => self.expr()?,
-                };
+    fn is_designator(&self) -> bool {
+        self.cur_token().expect_symbol("[").is_ok() || self.cur_token().expect_symbol(".").is_ok()
+    }
 
-                // inc
-                let _ = &self.next_token();
-                let inc = match self.cur_token().expect_symbol(")") {
-                    Ok(_n) => None,
-                    Err(_) => self.expr()?,
-                };
+    // designation = ("[" const_expr "]" | "." ident)+ "=" initializer
+    // the designated element is initialized again even if it is already given (e.g. "{1, [0] = 2}" is "{2}"),
+    // returns the index of the designated child of init
+    fn designation(&mut self, init: &mut Initializer) -> Result<usize, CompileError> {
+        let i = self.designator(init)?;
+        let child = &mut init.children[i];
+
+        // nested designator (e.g. ".p.x = 1", "[1][0] = 2") initializes the part of the child
+        if self.is_designator() {
+            self.designation(child)?;
+            return Ok(i);
+        }
 
-                // then
-                let _ = &self.next_token();
-                let then = self.stmt()?;
+        self.stmt_expect_symbol("=")?;
+        *child = Initializer::new(child.ty.clone());
+        self.initializer(child)?;
+        Ok(i)
+    }
 
-                let node = NodeKind::For { init, cond, inc, then }.wrap();
-                return Ok(node);
+    // "[" const_expr "]" for array, "." ident for struct/union
+    fn designator(&mut self, init: &mut Initializer) -> Result<usize, CompileError> {
+
+        if self.cur_token().expect_symbol("[").is_ok() {
+            let Some(len) = init.ty.array_len() else {
+                return Err(self.cur_token().error("array index in non-array initializer"));
+            };
+            let _ = &self.next_token();
+            let index = self.const_expr()?;
+            if index < 0 || len >= 0 && index >= len {
+                return Err(CompileError::new(&[&format!("array index: {} in initializer exceeds array bounds in {}", index, &self.cur_func)]));
+            }
+            self.stmt_expect_symbol("]")?;
+
+            // array of unknown length is as long as the largest index
+            while init.children.len() <= index as usize {
+                init.children.push(Initializer::new(init.ty.base().unwrap().clone()));
+            }
+            return Ok(index as usize);
+        }
+
+        if !init.ty.is_aggregate() {
+            return Err(self.cur_token().error("field name not in struct or union initializer"));
+        }
+        let Some(name) = self.cur_token().next.as_deref().and_then(|next| next.expect_ident().ok()) else {
+            return Err(self.cur_token().error("expected a member name"));
+        };
+
+        // the member of anonymous struct/union is designated through it, so leave ". ident" for the next designator
+        let members = init.ty.members();
+        let i = match members.iter().position(|m| m.name.as_deref() == Some(name)) {
+            Some(i) => {
+                let _ = &self.next_token();
+                let _ = &self.next_token();
+                i
+            },
+            None => match members.iter().position(|m| m.name.is_none() && m.ty.find_member(name).is_some()) {
+                Some(i) => i,
+                None => return Err(CompileError::new(&[&format!("no member: {} in initializer in {}", name, &self.cur_func)])),
+            },
+        };
+        init.member = i;
+        Ok(i)
+    }
+
+    // array/struct/union without braces takes the elements until it is filled (e.g. "{1, 2}" of "int a[2][2] = {1, 2, 3, 4};")
+    // designator is for the enclosing braces, so it ends this
+    fn elided_initializer(&mut self, init: &mut Initializer, mut first: Option<Option<Box<Node>>>) -> Result<(), CompileError> {
+        let len = init.positional_len();
+        for (i, child) in init.children.iter_mut().take(len).enumerate() {
+            // the expression already read is for the first element
+            if let Some(expr) = first.take() {
+                self.expr_initializer(child, expr)?;
+                continue;
+            }
+
+            if self.is_initializer_end() {
+                break;
+            }
+            if i > 0 {
+                let is_designated = self.cur_token().next.as_deref().is_some_and(|next| {
+                    next.expect_symbol("[").is_ok() || next.expect_symbol(".").is_ok()
+                });
+                if is_designated {
+                    break;
+                }
+                self.stmt_expect_symbol(",")?;
+            }
+            self.initializer(child)?;
+        }
+
+        // no element to take the expression (e.g. struct without members)
+        if first.is_some() {
+            eprintln!("warning: excess elements in initializer in {}", &self.cur_func);
+        }
+        Ok(())
+    }
+
+    // the elements over the length of array or the number of members are evaluated, but ignored
+    fn skip_excess_initializer(&mut self) -> Result<(), CompileError> {
+        eprintln!("warning: excess elements in initializer in {}", &self.cur_func);
+
+        if self.cur_token().expect_symbol("{").is_ok() {
+            let _ = &self.next_token();
+            while !self.is_initializer_end() {
+                self.skip_excess_initializer()?;
+                if let Ok(_) = self.cur_token().expect_symbol(",") && !self.is_initializer_end() {
+                    let _ = &self.next_token();
+                }
+            }
+            return self.initializer_end();
+        }
+
+        let _ = self.assign()?;
+        Ok(())
+    }
+
+    // "}" or "," "}" closes the brace initializer
+    fn is_initializer_end(&self) -> bool {
+        let token = self.cur_token();
+        if token.expect_symbol("}").is_ok() {
+            return true;
+        }
+        token.expect_symbol(",").is_ok() && token.next.as_deref().is_some_and(|next| next.expect_symbol("}").is_ok())
+    }
+
+    fn initializer_end(&mut self) -> Result<(), CompileError> {
+        if self.cur_token().expect_symbol(",").is_ok() {
+            let _ = &self.next_token();
+        }
+        self.stmt_expect_symbol("}")
+    }
+
+    // local variable is cleared at first if it is array/struct/union, then the given values are assigned
+    fn local_initializer(name: &str, offset: i32, init: Initializer) -> Option<Box<Node>> {
+        let mut body: Vec<Option<Box<Node>>> = Vec::new();
+        if init.ty.is_array() || init.ty.is_aggregate() {
+            body.push(NodeKind::MemZero { offset, size: init.ty.size() }.wrap());
+        }
+        Self::local_init_assigns(name, offset, init, &mut body);
+        NodeKind::Block { body }.wrap()
+    }
+
+    // the element in a variable is treated as a local variable of the element type at its offset
+    fn local_init_assigns(name: &str, offset: i32, init: Initializer, body: &mut Vec<Option<Box<Node>>>) {
+        if init.expr.is_some() {
+            let lhs = NodeKind::Lvar { name: name.to_string(), offset, ty: init.ty }.wrap();
+            body.push(NodeKind::ExprStmt { lhs: Self::new_assign(lhs, init.expr) }.wrap());
+            return;
+        }
+
+        for (child, child_offset) in init.into_children() {
+            // the offset of local variable is from rbp to the head, so the latter element is nearer to rbp
+            Self::local_init_assigns(name, offset - child_offset, child, body);
+        }
+    }
+
+    // the initial bytes of global variable and the addresses to be resolved by the linker
+    fn global_init(&self, init: Initializer) -> Result<(Vec<u8>, Vec<Relocation>), CompileError> {
+        let mut data = vec![0; init.ty.size() as usize];
+        let mut relocs = Vec::new();
+        self.global_data(init, &mut data, &mut relocs, 0)?;
+        Ok((data, relocs))
+    }
+
+    // write the initial values of global variable to data (the bytes not given are zero)
+    fn global_data(&self, init: Initializer, data: &mut [u8], relocs: &mut Vec<Relocation>, offset: usize) -> Result<(), CompileError> {
+        if let Some(mut expr) = init.expr {
+            let from = expr.ty().clone();
+            let bytes = match init.ty.unqualified() {
+                Ty::Float => (self.eval_flonum(&expr)? as f32).to_le_bytes().to_vec(),
+                Ty::Double => self.eval_flonum(&expr)?.to_le_bytes().to_vec(),
+                // pointer can be initialized by the address of global variable or function (e.g. "&x + 1", "a", "\"abc\"")
+                ty if ty.base().is_some() && !ty.is_array() => match self.eval_address(&expr)? {
+                    (Some(label), addend) => {
+                        relocs.push(Relocation { offset, label, addend });
+                        return Ok(());
+                    },
+                    (None, value) => value.to_le_bytes().to_vec(),
+                },
+                ty if ty.is_integer() => {
+                    let value = match from.is_flonum() {
+                        true => self.eval_flonum(&expr)? as i64,
+                        false => self.eval(&expr)? as i64,
+                    };
+                    value.to_le_bytes()[..ty.size() as usize].to_vec()
+                },
+                _ => return Err(CompileError::new(&["initializer element is not constant"])),
+            };
+            data[offset..offset + bytes.len()].copy_from_slice(&bytes);
+            return Ok(());
+        }
+
+        for (child, child_offset) in init.into_children() {
+            self.global_data(child, data, relocs, offset + child_offset as usize)?;
+        }
+        Ok(())
+    }
+
+    // declspec = ("typedef" | "static" | "extern" | "const" | "volatile" | "restrict" | type | typedef_name)+
+    // attr is None where storage class is not allowed (e.g. parameters, members)
+    fn declspec(&mut self, mut attr: Option<&mut VarAttr>) -> Result<Ty, CompileError> {
+
+        let mut ty: Option<Ty> = None;
+        let mut qual = Qualifiers::default();
+        let mut is_unsigned: Option<bool> = None; // "signed" or "unsigned" is given
+
+        loop {
+            let token = self.cur_token();
+
+            // signedness can be before or after char and int (e.g. "unsigned char", "int unsigned")
+            if let TokenKind::Type(name) = &token.kind && matches!(name.as_str(), "signed" | "unsigned") {
+                if is_unsigned.is_some() {
+                    return Err(token.error("multiple signed or unsigned in declaration specifiers"));
+                }
+                is_unsigned = Some(name == "unsigned");
+                let _ = &self.next_token();
+                continue;
+            }
+
+            // qualifiers can be before or after the type (e.g. "const int", "int const")
+            if let TokenKind::Type(name) = &token.kind && Self::add_qualifier(&mut qual, name) {
+                let _ = &self.next_token();
+                continue;
+            }
+
+            if let TokenKind::Type(name) = &token.kind && ["typedef", "static", "extern"].contains(&name.as_str()) {
+                let Some(attr) = attr.as_deref_mut() else {
+                    return Err(token.error("storage class specifier is not allowed in this context"));
+                };
+                if attr.is_typedef || attr.is_static || attr.is_extern {
+                    return Err(token.error("multiple storage classes in declaration specifiers"));
+                }
+                match name.as_str() {
+                    "typedef" => attr.is_typedef = true,
+                    "static" => attr.is_static = true,
+                    _ => attr.is_extern = true,
+                }
+                let _ = &self.next_token();
+                continue;
+            }
+
+            // after the type or signedness, typedef name is the name of declarator (e.g. "typedef int a; int b; a b;", "unsigned a;")
+            if !self.is_typename(token) || ty.is_some() || is_unsigned.is_some() && matches!(token.kind, TokenKind::Ident(_)) {
+                break;
+            }
+
+            if let TokenKind::Ident(name) = &token.kind {
+                ty = self.find_typedef(name);
+                let _ = &self.next_token();
+                continue;
+            }
+
+            let v_type_name = token.expect_type()?.to_string();
+            let _ = &self.next_token();
+
+            ty = Some(match v_type_name.as_str() {
+                "struct" | "union" => self.struct_decl(v_type_name == "union")?,
+                "enum" => self.enum_decl()?,
+                _ => match v_type_name.parse::<Ty>() {
+                    Ok(v_type) => v_type,
+                    Err(_) => {
+                        return Err(CompileError::new(&[&format!("type: {} is not defined in {}", v_type_name, &self.cur_func)]));
+                    }
+                },
+            });
+        }
+
+        // "signed" and "unsigned" alone are int, and char is signed
+        let ty = match (is_unsigned, ty) {
+            (None, ty) => ty,
+            (Some(is_unsigned), None) => Some(if is_unsigned { Ty::UInt } else { Ty::Int }),
+            (Some(true), Some(Ty::Char)) => Some(Ty::UChar),
+            (Some(true), Some(Ty::Int)) => Some(Ty::UInt),
+            (Some(false), Some(ty @ (Ty::Char | Ty::Int))) => Some(ty),
+            (Some(_), Some(ty)) => return Err(self.cur_token().error(&format!("signed or unsigned is not allowed with {}", ty))),
+        };
+
+        match ty {
+            Some(ty) if qual.is_restrict && !matches!(ty.unqualified(), Ty::Pointer { .. }) => {
+                Err(self.cur_token().error("restrict requires a pointer type"))
+            },
+            Some(ty) => Ok(ty.qualified(qual)),
+            None => Err(self.cur_token().error("expected a type")),
+        }
+    }
+
+    // register typedef names until ";" (e.g. "typedef int *p, **pp;")
+    fn parse_typedef(&mut self, base_type: Ty) -> Result<(), CompileError> {
+        while self.cur_token().expect_symbol(";").is_err() {
+
+            if self.cur_token().expect_symbol(",").is_ok() {
+                let _ = &self.next_token();
+            }
+
+            let (name, ty) = self.declarator(base_type.clone())?;
+            self.push_ident(name, Ident::Typedef(ty))?;
+        }
+        self.stmt_expect_symbol(";")?;
+        Ok(())
+    }
+
+    // declarator = "*"* ("(" declarator ")" | ident) type_suffix
+    fn declarator(&mut self, base_type: Ty) -> Result<(String, Ty), CompileError> {
+        let (v_name, ty) = self.declarator_inner(base_type, false)?;
+        Ok((v_name.unwrap(), ty))
+    }
+
+    // abstract_declarator = "*"* ("(" abstract_declarator ")")? type_suffix
+    // (declarator without name, used by type name. e.g. "int (*)[3]")
+    fn abstract_declarator(&mut self, base_type: Ty) -> Result<Ty, CompileError> {
+        let (_, ty) = self.declarator_inner(base_type, true)?;
+        Ok(ty)
+    }
+
+    // declarator or abstract_declarator, name is None only for abstract one
+    fn declarator_inner(&mut self, base_type: Ty, is_abstract: bool) -> Result<(Option<String>, Ty), CompileError> {
+        // while "*" continues, creates Ty including original type
+        let mut ty = base_type;
+        while self.cur_token().expect_symbol("*").is_ok() {
+            ty = Ty::new_pointer(ty);
+            let _ = &self.next_token();
+            ty = ty.qualified(self.pointer_qualifiers());
+        }
+
+        if self.is_nested_declarator(is_abstract) {
+            // the inner declarator modifies the type after type_suffix (e.g. "int (*x)[3]" is pointer to array),
+            // so skip the inner one at first, read type_suffix, then come back to the inner one
+            let start = self.token_iter.clone();
+            let _ = &self.next_token();
+            let _ = self.declarator_inner(Ty::Int, is_abstract)?;
+            self.stmt_expect_symbol(")")?;
+
+            ty = self.type_suffix(ty)?;
+            let end = self.token_iter.clone();
+
+            self.token_iter = start;
+            let _ = &self.next_token();
+            let declared = self.declarator_inner(ty, is_abstract)?;
+            self.token_iter = end;
+            return Ok(declared);
+        }
+
+        let v_name = match (is_abstract, self.cur_token().expect_ident()) {
+            (_, Ok(name)) => {
+                let name = name.to_string();
+                let _ = &self.next_token();
+                Some(name)
+            },
+            (true, Err(_)) => None,
+            (false, Err(e)) => return Err(e),
+        };
+
+        ty = self.type_suffix(ty)?;
+        Ok((v_name, ty))
+    }
+
+    // qualifiers after "*" are of the pointer itself (e.g. "int *const p" is const pointer to int)
+    fn pointer_qualifiers(&mut self) -> Qualifiers {
+        let mut qual = Qualifiers::default();
+        while let TokenKind::Type(name) = &self.cur_token().kind && Self::add_qualifier(&mut qual, name) {
+            let _ = &self.next_token();
+        }
+        qual
+    }
+
+    // returns false if name is not a qualifier
+    fn add_qualifier(qual: &mut Qualifiers, name: &str) -> bool {
+        match name {
+            "const" => qual.is_const = true,
+            "volatile" => qual.is_volatile = true,
+            "restrict" => qual.is_restrict = true,
+            _ => return false,
+        }
+        true
+    }
+
+    // "(" starts nested declarator, or parameters of abstract declarator (e.g. "int (*)(int)")
+    fn is_nested_declarator(&self, is_abstract: bool) -> bool {
+        if self.cur_token().expect_symbol("(").is_err() {
+            return false;
+        }
+        if !is_abstract {
+            return true;
+        }
+
+        match self.cur_token().next.as_deref() {
+            Some(next) => ["*", "(", "["].iter().any(|s| next.expect_symbol(s).is_ok()),
+            None => false,
+        }
+    }
+
+    // type_suffix = "(" func_params | "[" const_expr? "]" type_suffix | ε
+    fn type_suffix(&mut self, ty: Ty) -> Result<Ty, CompileError> {
+
+        if self.cur_token().expect_symbol("(").is_ok() {
+            let _ = &self.next_token();
+            return self.func_params(ty);
+        }
+
+        if self.cur_token().expect_symbol("[").is_ok() {
+            let _ = &self.next_token();
+
+            // the length can be omitted if it is given by initializer (e.g. "int a[] = {1, 2};")
+            let len = match self.cur_token().expect_symbol("]") {
+                Ok(_) => -1,
+                Err(_) => {
+                    let len = self.const_expr()?;
+                    if len < 0 {
+                        return Err(CompileError::new(&[&format!("array size is negative in {}", &self.cur_func)]));
+                    }
+                    len
+                },
+            };
+            self.stmt_expect_symbol("]")?;
+
+            // "int x[2][3]" is array of 2 (array of 3 int)
+            let ty = self.type_suffix(ty)?;
+            if !ty.is_complete() {
+                return Err(CompileError::new(&[&format!("array of incomplete type in {}", &self.cur_func)]));
+            }
+            return Ok(Ty::new_array(ty, len));
+        }
+
+        Ok(ty)
+    }
+
+    // func_params = ("void" | param ("," param)* ("," "...")?)? ")"
+    // param       = declspec (declarator | abstract_declarator)
+    fn func_params(&mut self, return_ty: Ty) -> Result<Ty, CompileError> {
+
+        // "()" doesn't specify the parameters, and "(void)" is no parameter
+        if self.cur_token().expect_symbol(")").is_ok() {
+            let _ = &self.next_token();
+            self.param_decls = Vec::new();
+            return Ok(Ty::new_unprototyped_func(return_ty.unqualified().clone()));
+        }
+        if let TokenKind::Type(name) = &self.cur_token().kind && name == "void"
+            && self.cur_token().next.as_deref().is_some_and(|next| next.expect_symbol(")").is_ok()) {
+            let _ = &self.next_token();
+            let _ = &self.next_token();
+            self.param_decls = Vec::new();
+            return Ok(Ty::new_func(return_ty.unqualified().clone(), Vec::new(), false));
+        }
+
+        // parameter name can be omitted in prototype (e.g. "int add(int, int);")
+        let mut param_decls: Vec<(Option<String>, Ty)> = Vec::new();
+        let mut is_variadic = false;
+        while self.cur_token().expect_symbol(")").is_err() {
+
+            if self.cur_token().expect_symbol(",").is_ok() {
+                let _ = &self.next_token();
+            }
+
+            // "..." must be the last
+            if self.cur_token().expect_symbol("...").is_ok() {
+                let _ = &self.next_token();
+                self.cur_token().expect_symbol(")")?;
+                is_variadic = true;
+                break;
+            }
+
+            let base_type = self.declspec(None)?;
+            let (v_name, ty) = self.declarator_inner(base_type, true)?;
+            param_decls.push((v_name, Self::param_type(ty)));
+        }
+        self.stmt_expect_symbol(")")?;
+
+        // the qualifiers of parameters are only for the variables in the function (e.g. "int f(const int x)" is "int (int)")
+        let params = param_decls.iter().map(|(_, ty)| ty.unqualified().clone()).collect();
+
+        // the parameters of the outer function are read after the inner ones (e.g. "int f(int (*g)(int y), int x)"),
+        // so the last ones are of the function being declared
+        self.param_decls = param_decls;
+        Ok(Ty::new_func(return_ty.unqualified().clone(), params, is_variadic))
+    }
+
+    // parameter of array or function is pointer actually (e.g. "int x[3]" is "int *x")
+    fn param_type(ty: Ty) -> Ty {
+        match ty {
+            Ty::Array { .. } | Ty::Func { .. } => ty.decay(),
+            _ => ty,
+        }
+    }
+
+    // typename = declspec abstract_declarator
+    fn typename(&mut self) -> Result<Ty, CompileError> {
+        let base_type = self.declspec(None)?;
+        self.abstract_declarator(base_type)
+    }
+
+    // struct_decl = ident? ("{" struct_members)?
+    // (union is parsed by the same rule, only the layout differs)
+    fn struct_decl(&mut self, is_union: bool) -> Result<Ty, CompileError> {
+
+        let kind = if is_union { "union" } else { "struct" };
+        let new_aggregate = |tag: Option<String>| if is_union { Ty::new_union(tag) } else { Ty::new_struct(tag) };
+
+        let tag = match self.cur_token().expect_ident() {
+            Ok(name) => {
+                let name = name.to_string();
+                let _ = &self.next_token();
+                Some(name)
+            },
+            Err(_) => None,
+        };
+
+        // refer to the struct by tag (e.g. "struct foo *p;")
+        if let (Some(tag), Err(_)) = (&tag, self.cur_token().expect_symbol("{")) {
+            if let Some(ty) = self.find_tag(tag) {
+                if ty.is_union() != is_union {
+                    return Err(CompileError::new(&[&format!("tag: {} is not {} in {}", tag, kind, &self.cur_func)]));
+                }
+                return Ok(ty);
+            }
+
+            // not defined yet, so declare as an incomplete struct. it will be completed by its definition
+            let ty = new_aggregate(Some(tag.clone()));
+            self.push_tag(tag.clone(), ty.clone());
+            return Ok(ty);
+        }
+
+        self.stmt_expect_symbol("{")?;
+
+        // register before members, so that members can refer to this struct itself (e.g. "struct node { struct node *next; };")
+        let ty = match &tag {
+            Some(tag) => match self.scopes.last().unwrap().tags.get(tag) {
+                Some(declared) if !declared.is_complete() && declared.is_union() == is_union => declared.clone(),
+                Some(_) => {
+                    return Err(CompileError::new(&[&format!("tag: {} is already defined in {}", tag, &self.cur_func)]));
+                },
+                None => {
+                    let ty = new_aggregate(Some(tag.clone()));
+                    self.push_tag(tag.clone(), ty.clone());
+                    ty
+                }
+            },
+            None => new_aggregate(None),
+        };
+
+        let members = self.struct_members()?;
+        ty.define_members(members)?;
+
+        Ok(ty)
+    }
+
+    // enum_decl = ident? ("{" enum_list "}")?
+    // enum_list = ident ("=" const_expr)? ("," ident ("=" const_expr)?)* ","?
+    fn enum_decl(&mut self) -> Result<Ty, CompileError> {
+
+        let tag = match self.cur_token().expect_ident() {
+            Ok(name) => {
+                let name = name.to_string();
+                let _ = &self.next_token();
+                Some(name)
+            },
+            Err(_) => None,
+        };
+
+        // refer to the enum by tag (e.g. "enum color c;")
+        if let (Some(tag), Err(_)) = (&tag, self.cur_token().expect_symbol("{")) {
+            return match self.find_tag(tag) {
+                Some(ty) if ty.is_enum() => Ok(ty),
+                Some(_) => Err(CompileError::new(&[&format!("tag: {} is not enum in {}", tag, &self.cur_func)])),
+                None => Err(CompileError::new(&[&format!("enum: {} is not defined in {}", tag, &self.cur_func)])),
+            };
+        }
+
+        self.stmt_expect_symbol("{")?;
+
+        // each enumerator is the previous value + 1 unless "=" is given
+        let mut value: i32 = 0;
+        while self.cur_token().expect_symbol("}").is_err() {
+
+            let name = self.cur_token().expect_ident()?.to_string();
+            let _ = &self.next_token();
+
+            if self.cur_token().expect_symbol("=").is_ok() {
+                let _ = &self.next_token();
+                value = self.const_expr()?;
+            }
+            self.push_ident(name, Ident::EnumConst(value))?;
+            value = value.wrapping_add(1);
+
+            match self.cur_token().expect_symbol(",") {
+                Ok(_) => { let _ = &self.next_token(); },
+                Err(_) => break,
+            }
+        }
+        self.stmt_expect_symbol("}")?;
+
+        if let Some(tag) = tag {
+            if self.scopes.last().unwrap().tags.contains_key(&tag) {
+                return Err(CompileError::new(&[&format!("tag: {} is already defined in {}", tag, &self.cur_func)]));
+            }
+            self.push_tag(tag, Ty::Enum);
+        }
+
+        Ok(Ty::Enum)
+    }
+
+    // struct_members = (declspec (declarator ("," declarator)*)? ";")* "}"
+    // (declarator can be omitted only for anonymous struct/union member)
+    fn struct_members(&mut self) -> Result<Vec<(Option<String>, Ty)>, CompileError> {
+
+        let mut members: Vec<(Option<String>, Ty)> = Vec::new();
+        while self.cur_token().expect_symbol("}").is_err() {
+
+            let base_type: Ty = self.declspec(None)?;
+
+            // anonymous struct/union, its members are promoted into the enclosing one
+            if let (true, Ok(_)) = (base_type.is_aggregate(), self.cur_token().expect_symbol(";")) {
+                let _ = &self.next_token();
+                members.push((None, base_type));
+                continue;
+            }
+
+            loop {
+                let (m_name, m_ty) = self.declarator(base_type.clone())?;
+                members.push((Some(m_name), m_ty));
+
+                match self.cur_token().expect_symbol(",") {
+                    Ok(_) => { let _ = &self.next_token(); },
+                    Err(_) => break,
+                }
+            }
+
+            self.stmt_expect_symbol(";")?;
+        }
+        self.stmt_expect_symbol("}")?;
+
+        Ok(members)
+    }
+
+
+
+    fn stmt(&mut self) -> Result<Option<Box<Node>>, CompileError> {
+
+
+        let cur = self.cur_token();
+
+        match cur {
+            // "return" expr ";"
+            Token { kind: TokenKind::Return, .. } => {
+                let _ = &self.next_token();
+
+                // the value is converted to the return type as if it is assigned
+                let r_type = self.symbols.get(&self.cur_func).unwrap().ty.return_ty().unwrap().unqualified().clone();
+                let mut lhs = self.expr()?;
+                let ty = lhs.as_mut().unwrap().ty().decay();
+                self.check_pointer_conversion(&ty, &r_type)?;
+                let Some(lhs) = self.new_cast_for_assign(lhs, &r_type) else {
+                    return Err(CompileError::new(&[&format!("return value has incompatible type ({} to {}) in {}", ty, r_type, &self.cur_func)]));
+                };
+
+                let node = NodeKind::Return { lhs, }.wrap();
+                self.stmt_expect_symbol(";")?;
+                Ok(node)
+            },
+            // "if" "(" expr ")" stmt ("else" stmt)?
+            Token { kind: TokenKind::If, .. } => {
 
-            },
-            // "while" "(" expr ")" stmt
-            Token { kind: TokenKind::While, .. } => {
 
                 let _ = &self.next_token();
                 self.stmt_expect_symbol("(")?;
@@ -451,8 +1614,195 @@ impl<'a> Parser<'a> {
                 // then
                 let then = self.stmt()?;
 
+                // else_then
+                match self.cur_token().at_else() {
+                    true => {
+                        let _ = &self.next_token();
+                        let node = NodeKind::If { cond, then, else_then: self.stmt()? }.wrap();
+                        Ok(node)
+                    },
+                    false => {
+                        let node = NodeKind::If { cond, then, else_then: None }.wrap();
+                        Ok(node)
+                    }
+                }
+
+            },
+            // "for" "(" expr? ";" expr? ";" expr? ")" stmt
+            Token { kind: TokenKind::For, .. } => {
+
+                let _ = &self.next_token();
+                self.stmt_expect_symbol("(")?;
+
+                // init
+                let init = match self.cur_token().expect_symbol(";") {
+                    Ok(_n) => None,
+                    Err(_) => NodeKind::ExprStmt { lhs: self.expr()? }.wrap(),
+                };
+
+                // cond
+                let _ = &self.next_token();
+                let cond = match self.cur_token().expect_symbol(";") {
+                    Ok(_n) => None,
+                    Err(_) => self.expr()?,
+                };
+
+                // inc
+                let _ = &self.next_token();
+                let inc = match self.cur_token().expect_symbol(")") {
+                    Ok(_n) => None,
+                    Err(_) => NodeKind::ExprStmt { lhs: self.expr()? }.wrap(),
+                };
+
+                // then
+                let _ = &self.next_token();
+                let then = self.loop_body()?;
+
+                let node = NodeKind::For { init, cond, inc, then }.wrap();
+                Ok(node)
+
+            },
+            // "while" "(" expr ")" stmt
+            Token { kind: TokenKind::While, .. } => {
+
+                let _ = &self.next_token();
+                self.stmt_expect_symbol("(")?;
+                // cond
+                let cond = self.expr()?;
+
+                self.stmt_expect_symbol(")")?;
+
+                // then
+                let then = self.loop_body()?;
+
                 let node = NodeKind::For { init: None, cond, inc: None, then }.wrap();
-                return Ok(node);
+                Ok(node)
+            },
+            // "do" stmt "while" "(" expr ")" ";"
+            Token { kind: TokenKind::Do, .. } => {
+
+                let _ = &self.next_token();
+                let then = self.loop_body()?;
+
+                let Token { kind: TokenKind::While, .. } = self.cur_token() else {
+                    return Err(self.cur_token().error("expected while"));
+                };
+                let _ = &self.next_token();
+                self.stmt_expect_symbol("(")?;
+                let cond = self.expr()?;
+                self.stmt_expect_symbol(")")?;
+                self.stmt_expect_symbol(";")?;
+
+                let node = NodeKind::DoWhile { then, cond }.wrap();
+                Ok(node)
+            },
+            // "switch" "(" expr ")" stmt
+            Token { kind: TokenKind::Switch, .. } => {
+
+                let _ = &self.next_token();
+                self.stmt_expect_symbol("(")?;
+                let mut cond = self.expr()?;
+                if !cond.as_mut().unwrap().ty().is_integer() {
+                    return Err(CompileError::new(&[&format!("switch quantity is not an integer in {}", &self.cur_func)]));
+                }
+                let cond = Self::new_cast(cond, &Ty::Int);
+                self.stmt_expect_symbol(")")?;
+
+                self.switches.push(SwitchCases { cases: Vec::new(), default: None, labels: 0 });
+                self.break_depth += 1;
+                let body = self.stmt();
+                self.break_depth -= 1;
+                let SwitchCases { cases, default, .. } = self.switches.pop().unwrap();
+
+                let node = NodeKind::Switch { cond, body: body?, cases, default }.wrap();
+                Ok(node)
+            },
+            // "case" const_expr ":" stmt
+            Token { kind: TokenKind::Case, .. } => {
+                if self.switches.is_empty() {
+                    return Err(cur.error("case label not within a switch statement"));
+                }
+                let _ = &self.next_token();
+                let value = self.const_expr()?;
+                self.stmt_expect_symbol(":")?;
+
+                let switch = self.switches.last_mut().unwrap();
+                if switch.cases.iter().any(|(v, _)| *v == value) {
+                    return Err(CompileError::new(&[&format!("duplicate case value: {} in {}", value, &self.cur_func)]));
+                }
+                let index = switch.labels;
+                switch.cases.push((value, index));
+                switch.labels += 1;
+
+                let node = NodeKind::Case { index, body: self.stmt()? }.wrap();
+                Ok(node)
+            },
+            // "default" ":" stmt
+            Token { kind: TokenKind::Default, .. } => {
+                match self.switches.last() {
+                    None => return Err(cur.error("default label not within a switch statement")),
+                    Some(switch) if switch.default.is_some() => return Err(cur.error("multiple default labels in one switch")),
+                    Some(_) => {},
+                }
+                let _ = &self.next_token();
+                self.stmt_expect_symbol(":")?;
+
+                let switch = self.switches.last_mut().unwrap();
+                let index = switch.labels;
+                switch.default = Some(index);
+                switch.labels += 1;
+
+                let node = NodeKind::Case { index, body: self.stmt()? }.wrap();
+                Ok(node)
+            },
+            // "goto" (ident | "*" expr) ";"
+            Token { kind: TokenKind::Goto, .. } => {
+                let _ = &self.next_token();
+                if self.cur_token().expect_symbol("*").is_ok() {
+                    let _ = &self.next_token();
+                    let mut target = self.expr()?;
+                    if target.as_mut().unwrap().ty().base().is_none() {
+                        return Err(CompileError::new(&[&format!("computed goto needs a pointer in {}", &self.cur_func)]));
+                    }
+                    self.stmt_expect_symbol(";")?;
+                    return Ok(NodeKind::GotoPtr { target }.wrap());
+                }
+
+                let name = self.cur_token().expect_ident()?.to_string();
+                let _ = &self.next_token();
+                self.stmt_expect_symbol(";")?;
+                self.goto_labels.push(name.clone());
+                Ok(NodeKind::Goto { name }.wrap())
+            },
+            // ident ":" stmt
+            Token { kind: TokenKind::Ident(name), next: Some(next), .. } if next.expect_symbol(":").is_ok() => {
+                if self.labels.contains(name) {
+                    return Err(cur.error("duplicate label"));
+                }
+                let name = name.to_string();
+                self.labels.insert(name.clone());
+                let _ = &self.next_token();
+                let _ = &self.next_token();
+                let node = NodeKind::Label { name, body: self.stmt()? }.wrap();
+                Ok(node)
+            },
+            // "break" ";"
+            Token { kind: TokenKind::Break, .. } => {
+                if self.break_depth == 0 {
+                    return Err(cur.error("break statement not within loop or switch"));
+                }
+                let _ = &self.next_token();
+                self.stmt_expect_symbol(";")?;
+                Ok(NodeKind::Break.wrap())
+            },
+            // "continue" ";"
+            Token { kind: TokenKind::Continue, .. } => {
+                if self.loop_depth == 0 {
+                    return Err(cur.error("continue statement not within loop"));
+                }
+                let _ = &self.next_token();
+                self.stmt_expect_symbol(";")?;
+                Ok(NodeKind::Continue.wrap())
             },
             _ => {
                 match cur.expect_symbol("{") {
@@ -460,7 +1810,7 @@ impl<'a> Parser<'a> {
                     Ok(_) => {
                         let _ = &self.next_token();
                         let node = self.compound_stmt()?;
-                        return Ok(node);
+                        Ok(node)
                     },
                     // expr? ";"
                     Err(_) => {
@@ -468,12 +1818,12 @@ impl<'a> Parser<'a> {
                             Ok(_) => {
                                 let _ = &self.next_token();
                                 let node = NodeKind::Block { body: Vec::new() }.wrap();
-                                return Ok(node);
+                                Ok(node)
                             },
                             Err(_) => {
-                                let node = self.expr()?;
+                                let node = NodeKind::ExprStmt { lhs: self.expr()? }.wrap();
                                 self.stmt_expect_symbol(";")?;
-                                return Ok(node);
+                                Ok(node)
                             }
                         }
                     }
@@ -482,28 +1832,384 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn stmt_expect_symbol(&mut self, symbol: &str) -> Result<(), CompileError> {
+    // the body of loop, where break and continue are allowed
+    fn loop_body(&mut self) -> Result<Option<Box<Node>>, CompileError> {
+        self.loop_depth += 1;
+        self.break_depth += 1;
+        let body = self.stmt();
+        self.loop_depth -= 1;
+        self.break_depth -= 1;
+        body
+    }
+
+    fn stmt_expect_symbol(&mut self, symbol: &str) -> Result<(), CompileError> {
+
+        self.cur_token().expect_symbol(symbol)?;
+        let _ = &self.next_token();
+        Ok(())
+    }
+
+
+    fn expr(&mut self) -> Result<Option<Box<Node>>, CompileError> {
+        let mut node: Option<Box<Node>> = self.assign()?;
+
+        loop {
+
+            if self.cur_token().expect_symbol(",").is_ok() {
+                let _ = &self.next_token();
+                node = NodeKind::Comma { lhs: node, rhs: self.assign()?, }.wrap();
+                continue;
+            }
+
+            return Ok(node);
+        }
+    }
+
+    // integer constant expression, which is evaluated at compile time (e.g. array length, case label, value of enum constant)
+    fn const_expr(&mut self) -> Result<i32, CompileError> {
+        let node = self.conditional()?;
+        self.eval(node.as_deref().unwrap())
+    }
+
+    // evaluate integer constant expression, overflow is warned and wrapped around like the generated code
+    fn eval(&self, node: &Node) -> Result<i32, CompileError> {
+
+        let binary = |lhs: &Option<Box<Node>>, rhs: &Option<Box<Node>>| -> Result<(i32, i32), CompileError> {
+            Ok((self.eval(lhs.as_deref().unwrap())?, self.eval(rhs.as_deref().unwrap())?))
+        };
+        // unsigned int is kept as the bit pattern in i32, and it wraps around without warning
+        let is_unsigned = |lhs: &Option<Box<Node>>| Self::is_unsigned_value(lhs.as_deref().unwrap());
+        let wrap = |lhs: &Option<Box<Node>>, checked: Option<i32>, wrapped: i32| match is_unsigned(lhs) {
+            true => wrapped,
+            false => self.overflow(checked, wrapped),
+        };
+
+        match &node.kind {
+            NodeKind::Num { value } => Ok(*value),
+            NodeKind::Neg { lhs } => self.eval(lhs.as_deref().unwrap()).map(|v| wrap(lhs, v.checked_neg(), v.wrapping_neg())),
+            NodeKind::Cast { lhs, ty } if ty.is_integer() => {
+                let lhs = lhs.as_deref().unwrap();
+                let value = match lhs.ty.as_ref().is_some_and(|t| t.is_flonum()) {
+                    true if ty.is_unsigned() => self.eval_flonum(lhs)? as u32 as i32,
+                    true => self.eval_flonum(lhs)? as i32,
+                    false => self.eval(lhs)?,
+                };
+                Ok(match ty {
+                    Ty::Char => value as i8 as i32,
+                    Ty::UChar => value as u8 as i32,
+                    _ => value,
+                })
+            },
+            NodeKind::Add { lhs, rhs } => binary(lhs, rhs).map(|(l, r)| wrap(lhs, l.checked_add(r), l.wrapping_add(r))),
+            NodeKind::Sub { lhs, rhs } => match binary(lhs, rhs) {
+                Ok((l, r)) => Ok(wrap(lhs, l.checked_sub(r), l.wrapping_sub(r))),
+                // the distance between the addresses in the same variable (e.g. "&a[3] - &a[1]")
+                Err(e) => match (self.eval_address(lhs.as_deref().unwrap()), self.eval_address(rhs.as_deref().unwrap())) {
+                    (Ok((Some(l_label), l)), Ok((Some(r_label), r))) if l_label == r_label => Ok((l - r) as i32),
+                    _ => Err(e),
+                },
+            },
+            NodeKind::Mul { lhs, rhs } => binary(lhs, rhs).map(|(l, r)| wrap(lhs, l.checked_mul(r), l.wrapping_mul(r))),
+            NodeKind::Div { lhs, rhs } => {
+                let (l, r) = binary(lhs, rhs)?;
+                if r == 0 {
+                    return Err(CompileError::new(&[&format!("division by zero in constant expression in {}", &self.cur_func)]));
+                }
+                match is_unsigned(lhs) {
+                    true => Ok((l as u32 / r as u32) as i32),
+                    false => Ok(self.overflow(l.checked_div(r), l.wrapping_div(r))),
+                }
+            },
+            NodeKind::Mod { lhs, rhs } => {
+                let (l, r) = binary(lhs, rhs)?;
+                if r == 0 {
+                    return Err(CompileError::new(&[&format!("division by zero in constant expression in {}", &self.cur_func)]));
+                }
+                match is_unsigned(lhs) {
+                    true => Ok((l as u32 % r as u32) as i32),
+                    false => Ok(self.overflow(l.checked_rem(r), l.wrapping_rem(r))),
+                }
+            },
+            NodeKind::BitAnd { lhs, rhs } => binary(lhs, rhs).map(|(l, r)| l & r),
+            NodeKind::BitOr { lhs, rhs } => binary(lhs, rhs).map(|(l, r)| l | r),
+            NodeKind::BitXor { lhs, rhs } => binary(lhs, rhs).map(|(l, r)| l ^ r),
+            NodeKind::BitNot { lhs } => self.eval(lhs.as_deref().unwrap()).map(|v| !v),
+            NodeKind::Shl { lhs, rhs } => binary(lhs, rhs).map(|(l, r)| self.shift_count(r).map_or(0, |r| l.wrapping_shl(r))),
+            NodeKind::Shr { lhs, rhs } if is_unsigned(lhs) => binary(lhs, rhs).map(|(l, r)| self.shift_count(r).map_or(0, |r| (l as u32 >> r) as i32)),
+            NodeKind::Shr { lhs, rhs } => binary(lhs, rhs).map(|(l, r)| self.shift_count(r).map_or(if l < 0 { -1 } else { 0 }, |r| l.wrapping_shr(r))),
+            NodeKind::Eq { lhs, rhs } => binary(lhs, rhs).map(|(l, r)| (l == r) as i32),
+            NodeKind::Ne { lhs, rhs } => binary(lhs, rhs).map(|(l, r)| (l != r) as i32),
+            NodeKind::Lt { lhs, rhs } if is_unsigned(lhs) => binary(lhs, rhs).map(|(l, r)| ((l as u32) < r as u32) as i32),
+            NodeKind::Le { lhs, rhs } if is_unsigned(lhs) => binary(lhs, rhs).map(|(l, r)| (l as u32 <= r as u32) as i32),
+            NodeKind::Lt { lhs, rhs } => binary(lhs, rhs).map(|(l, r)| (l < r) as i32),
+            NodeKind::Le { lhs, rhs } => binary(lhs, rhs).map(|(l, r)| (l <= r) as i32),
+            // rhs is not evaluated if the result is decided by lhs (e.g. "0 && 1/0")
+            NodeKind::LogAnd { lhs, rhs } => match self.eval(lhs.as_deref().unwrap())? {
+                0 => Ok(0),
+                _ => self.eval(rhs.as_deref().unwrap()).map(|r| (r != 0) as i32),
+            },
+            NodeKind::LogOr { lhs, rhs } => match self.eval(lhs.as_deref().unwrap())? {
+                0 => self.eval(rhs.as_deref().unwrap()).map(|r| (r != 0) as i32),
+                _ => Ok(1),
+            },
+            NodeKind::Not { lhs } => self.eval(lhs.as_deref().unwrap()).map(|v| (v == 0) as i32),
+            NodeKind::Cond { cond, then, els, .. } => match self.eval(cond.as_deref().unwrap())? {
+                0 => self.eval(els.as_deref().unwrap()),
+                _ => self.eval(then.as_deref().unwrap()),
+            },
+            _ => Err(CompileError::new(&[&format!("not a constant expression in {}", &self.cur_func)])),
+        }
+    }
+
+    // the operands have their types already, because the usual arithmetic conversions refer to them (or convert them by Cast)
+    fn is_unsigned_value(node: &Node) -> bool {
+        match &node.kind {
+            NodeKind::Cast { ty, .. } => ty.is_unsigned(),
+            _ => node.ty.as_ref().is_some_and(|t| t.is_unsigned()),
+        }
+    }
+
+    // checked is None if the result of int operation overflows
+    fn overflow(&self, checked: Option<i32>, wrapped: i32) -> i32 {
+        checked.unwrap_or_else(|| {
+            eprintln!("warning: integer overflow in constant expression in {}", &self.cur_func);
+            wrapped
+        })
+    }
+
+    // shift count must be in 0..32 (the width of int), otherwise all bits are shifted out
+    fn shift_count(&self, count: i32) -> Option<u32> {
+        if !(0..32).contains(&count) {
+            eprintln!("warning: shift count {} is out of range in constant expression in {}", count, &self.cur_func);
+            return None;
+        }
+        Some(count as u32)
+    }
+
+    // evaluate address constant as (label, offset), label is None if it is just a number (e.g. "(int *)0")
+    // (e.g. "&x + 1" is ("x", 4), "a" of global array is ("a", 0), string literal is (".L.str.N", 0))
+    fn eval_address(&self, node: &Node) -> Result<(Option<String>, i64), CompileError> {
+
+        // the pointer operand of pointer arithmetic has its type already (the index is multiplied by the size of base)
+        let is_pointer = |lhs: &Option<Box<Node>>| lhs.as_deref().unwrap().ty.as_ref().is_some_and(|t| t.base().is_some());
+
+        match &node.kind {
+            // array and function are converted to the address of the head (e.g. "a", "a[1]" of 2D array, "s.a")
+            NodeKind::Gvar { ty, .. } | NodeKind::Member { ty, .. } if ty.is_array() => self.eval_lval_address(node),
+            NodeKind::Deref { .. } if node.ty.as_ref().is_some_and(|t| t.is_array()) => self.eval_lval_address(node),
+            NodeKind::FuncRef { name, .. } => Ok((Some(name.clone()), 0)),
+            NodeKind::Addr { lhs } => self.eval_lval_address(lhs.as_deref().unwrap()),
+            NodeKind::Cast { lhs, ty } if ty.base().is_some() => self.eval_address(lhs.as_deref().unwrap()),
+            NodeKind::Add { lhs, rhs } if is_pointer(lhs) => {
+                let (label, offset) = self.eval_address(lhs.as_deref().unwrap())?;
+                Ok((label, offset + self.eval(rhs.as_deref().unwrap())? as i64))
+            },
+            NodeKind::Sub { lhs, rhs } if is_pointer(lhs) => {
+                let (label, offset) = self.eval_address(lhs.as_deref().unwrap())?;
+                Ok((label, offset - self.eval(rhs.as_deref().unwrap())? as i64))
+            },
+            NodeKind::Cond { cond, then, els, .. } => match self.eval(cond.as_deref().unwrap())? {
+                0 => self.eval_address(els.as_deref().unwrap()),
+                _ => self.eval_address(then.as_deref().unwrap()),
+            },
+            _ => self.eval(node).map(|value| (None, value as i64)),
+        }
+    }
+
+    // the address of lvalue which is global variable or its element/member (e.g. "&a[2]", "&s.x", "&*p")
+    fn eval_lval_address(&self, node: &Node) -> Result<(Option<String>, i64), CompileError> {
+        match &node.kind {
+            NodeKind::Gvar { name, .. } | NodeKind::FuncRef { name, .. } => Ok((Some(name.clone()), 0)),
+            NodeKind::Member { lhs, offset, .. } => {
+                let (label, base) = self.eval_lval_address(lhs.as_deref().unwrap())?;
+                Ok((label, base + *offset as i64))
+            },
+            NodeKind::Deref { lhs } => self.eval_address(lhs.as_deref().unwrap()),
+            _ => Err(CompileError::new(&[&format!("not a constant address in {}", &self.cur_func)])),
+        }
+    }
+
+    // _Static_assert "(" const_expr ("," string)? ")" ";"
+    fn static_assert(&mut self) -> Result<(), CompileError> {
+        let _ = &self.next_token();
+        self.stmt_expect_symbol("(")?;
+        let value = self.const_expr()?;
+
+        // the message is optional since C23
+        let mut message = String::new();
+        if self.cur_token().expect_symbol(",").is_ok() {
+            let _ = &self.next_token();
+            match &self.cur_token().kind {
+                TokenKind::Str(bytes) => message = String::from_utf8_lossy(bytes).to_string(),
+                _ => return Err(self.cur_token().error("expected a string literal")),
+            }
+            let _ = &self.next_token();
+        }
+        self.stmt_expect_symbol(")")?;
+        self.stmt_expect_symbol(";")?;
+
+        if value == 0 {
+            return Err(CompileError::new(&[&format!("static assertion failed: \"{}\" in {}", message, &self.cur_func)]));
+        }
+        Ok(())
+    }
+
+    // evaluate floating point constant expression (e.g. initializer of global double), integer one is also accepted
+    fn eval_flonum(&self, node: &Node) -> Result<f64, CompileError> {
+
+        if let Ok(value) = self.eval(node) {
+            return Ok(if Self::is_unsigned_value(node) { value as u32 as f64 } else { value as f64 });
+        }
+
+        let binary = |lhs: &Option<Box<Node>>, rhs: &Option<Box<Node>>| -> Result<(f64, f64), CompileError> {
+            Ok((self.eval_flonum(lhs.as_deref().unwrap())?, self.eval_flonum(rhs.as_deref().unwrap())?))
+        };
+
+        match &node.kind {
+            NodeKind::FNum { value, ty } => Ok(if matches!(ty, Ty::Float) { *value as f32 as f64 } else { *value }),
+            NodeKind::Neg { lhs } => self.eval_flonum(lhs.as_deref().unwrap()).map(|v| -v),
+            NodeKind::Cast { lhs, ty: Ty::Float } => self.eval_flonum(lhs.as_deref().unwrap()).map(|v| v as f32 as f64),
+            NodeKind::Cast { lhs, ty: Ty::Double } => self.eval_flonum(lhs.as_deref().unwrap()),
+            NodeKind::Add { lhs, rhs } => binary(lhs, rhs).map(|(l, r)| l + r),
+            NodeKind::Sub { lhs, rhs } => binary(lhs, rhs).map(|(l, r)| l - r),
+            NodeKind::Mul { lhs, rhs } => binary(lhs, rhs).map(|(l, r)| l * r),
+            NodeKind::Div { lhs, rhs } => binary(lhs, rhs).map(|(l, r)| l / r),
+            NodeKind::Cond { cond, then, els, .. } => match self.eval_flonum(cond.as_deref().unwrap())? != 0.0 {
+                true => self.eval_flonum(then.as_deref().unwrap()),
+                false => self.eval_flonum(els.as_deref().unwrap()),
+            },
+            _ => Err(CompileError::new(&[&format!("not a constant expression in {}", &self.cur_func)])),
+        }
+    }
+
+    fn assign(&mut self) -> Result<Option<Box<Node>>, CompileError> {
+        let mut node: Option<Box<Node>> = self.conditional()?;
+
+        if self.cur_token().expect_symbol("=").is_ok() {
+            let _ = &self.next_token();
+            let mut rhs = self.assign()?;
+            self.check_modifiable(&mut node)?;
+            let lty = node.as_mut().unwrap().ty().clone();
+            self.check_pointer_conversion(rhs.as_mut().unwrap().ty(), &lty)?;
+
+            // the value must be convertible to the type of lhs (e.g. "s = 1" for struct s, "p = 3" for pointer p are invalid)
+            let from = rhs.as_mut().unwrap().ty().decay();
+            let rhs = match self.new_cast_for_assign(rhs, &lty) {
+                Some(rhs) => rhs,
+                None => return Err(CompileError::new(&[&format!("incompatible types when assigning to {} from {} in {}", lty, from, &self.cur_func)])),
+            };
+            node = Self::new_assign(node, rhs);
+            return Ok(node);
+        }
+
+        // compound assignment (e.g. "x += 1")
+        for op in ["+", "-", "*", "/", "%", "&", "|", "^", "<<", ">>"] {
+            if self.cur_token().expect_symbol(&format!("{}=", op)).is_ok() {
+                let _ = &self.next_token();
+                let rhs = self.assign()?;
+                return self.new_compound_assign(op, node, rhs);
+            }
+        }
+
+        Ok(node)
+    }
+
+    fn conditional(&mut self) -> Result<Option<Box<Node>>, CompileError> {
+        let cond: Option<Box<Node>> = self.log_or()?;
+
+        let Ok(_) = self.cur_token().expect_symbol("?") else {
+            return Ok(cond);
+        };
+        let _ = &self.next_token();
+        let then = self.expr()?;
+        self.stmt_expect_symbol(":")?;
+        let els = self.conditional()?;
+
+        self.new_cond(cond, then, els)
+    }
+
+    fn log_or(&mut self) -> Result<Option<Box<Node>>, CompileError> {
+        let mut node: Option<Box<Node>> = self.log_and()?;
+
+        loop {
+
+            if self.cur_token().expect_symbol("||").is_ok() {
+                let _ = &self.next_token();
+                node = NodeKind::LogOr { lhs: node, rhs: self.log_and()?, }.wrap();
+                continue;
+            }
+
+            return Ok(node);
+        }
+    }
+
+    fn log_and(&mut self) -> Result<Option<Box<Node>>, CompileError> {
+        let mut node: Option<Box<Node>> = self.bit_or()?;
+
+        loop {
+
+            if self.cur_token().expect_symbol("&&").is_ok() {
+                let _ = &self.next_token();
+                node = NodeKind::LogAnd { lhs: node, rhs: self.bit_or()?, }.wrap();
+                continue;
+            }
+
+            return Ok(node);
+        }
+    }
+
+    fn bit_or(&mut self) -> Result<Option<Box<Node>>, CompileError> {
+        let mut node: Option<Box<Node>> = self.bit_xor()?;
 
-        let _ = self.cur_token().expect_symbol(symbol)?;
-        let _ = &self.next_token();
-        Ok(())
+        loop {
+
+            if self.cur_token().expect_symbol("|").is_ok() {
+                let _ = &self.next_token();
+                let mut rhs = self.bit_xor()?;
+                self.check_integer_operands(&mut node, &mut rhs, "|")?;
+                let (lhs, rhs) = Self::usual_arith_conv(node, rhs);
+                node = NodeKind::BitOr { lhs, rhs, }.wrap();
+                continue;
+            }
+
+            return Ok(node);
+        }
     }
 
+    fn bit_xor(&mut self) -> Result<Option<Box<Node>>, CompileError> {
+        let mut node: Option<Box<Node>> = self.bit_and()?;
 
-    fn expr(&mut self) -> Result<Option<Box<Node>>, CompileError> {
-        self.assign()
+        loop {
+
+            if self.cur_token().expect_symbol("^").is_ok() {
+                let _ = &self.next_token();
+                let mut rhs = self.bit_and()?;
+                self.check_integer_operands(&mut node, &mut rhs, "^")?;
+                let (lhs, rhs) = Self::usual_arith_conv(node, rhs);
+                node = NodeKind::BitXor { lhs, rhs, }.wrap();
+                continue;
+            }
+
+            return Ok(node);
+        }
     }
 
-    fn assign(&mut self) -> Result<Option<Box<Node>>, CompileError> {
+    fn bit_and(&mut self) -> Result<Option<Box<Node>>, CompileError> {
         let mut node: Option<Box<Node>> = self.equality()?;
 
-        if let Ok(_) = self.cur_token().expect_symbol("=") {
-            let _ = &self.next_token();
-            node = NodeKind::Assign { lhs: node, rhs: self.assign()?, }.wrap();
+        loop {
+
+            if self.cur_token().expect_symbol("&").is_ok() {
+                let _ = &self.next_token();
+                let mut rhs = self.equality()?;
+                self.check_integer_operands(&mut node, &mut rhs, "&")?;
+                let (lhs, rhs) = Self::usual_arith_conv(node, rhs);
+                node = NodeKind::BitAnd { lhs, rhs, }.wrap();
+                continue;
+            }
+
             return Ok(node);
         }
-
-        return Ok(node);
     }
 
     fn equality(&mut self) -> Result<Option<Box<Node>>, CompileError> {
@@ -511,15 +2217,19 @@ impl<'a> Parser<'a> {
 
         loop {
 
-            if let Ok(_) = self.cur_token().expect_symbol("==") {
+            if self.cur_token().expect_symbol("==").is_ok() {
                 let _ = &self.next_token();
-                node = NodeKind::Eq { lhs: node, rhs: self.relational()?, }.wrap();
+                let rhs = self.relational()?;
+                let (lhs, rhs) = Self::usual_arith_conv(node, rhs);
+                node = NodeKind::Eq { lhs, rhs, }.wrap();
                 continue;
             }
 
-            if let Ok(_) = self.cur_token().expect_symbol("!=") {
+            if self.cur_token().expect_symbol("!=").is_ok() {
                 let _ = &self.next_token();
-                node = NodeKind::Ne { lhs: node, rhs: self.relational()?, }.wrap();
+                let rhs = self.relational()?;
+                let (lhs, rhs) = Self::usual_arith_conv(node, rhs);
+                node = NodeKind::Ne { lhs, rhs, }.wrap();
                 continue;
             }
 
@@ -528,31 +2238,67 @@ impl<'a> Parser<'a> {
     }
 
     fn relational(&mut self) -> Result<Option<Box<Node>>, CompileError> {
-        let mut node: Option<Box<Node>> = self.add()?;
+        let mut node: Option<Box<Node>> = self.shift()?;
 
         loop {
 
-            if let Ok(_) = self.cur_token().expect_symbol("<") {
+            if self.cur_token().expect_symbol("<").is_ok() {
+                let _ = &self.next_token();
+                let rhs = self.shift()?;
+                let (lhs, rhs) = Self::usual_arith_conv(node, rhs);
+                node = NodeKind::Lt { lhs, rhs, }.wrap();
+                continue;
+            }
+
+            if self.cur_token().expect_symbol("<=").is_ok() {
+                let _ = &self.next_token();
+                let rhs = self.shift()?;
+                let (lhs, rhs) = Self::usual_arith_conv(node, rhs);
+                node = NodeKind::Le { lhs, rhs, }.wrap();
+                continue;
+            }
+
+            if self.cur_token().expect_symbol(">").is_ok() {
                 let _ = &self.next_token();
-                node = NodeKind::Lt { lhs: node, rhs: self.add()?, }.wrap();
+                let lhs = self.shift()?;
+                let (lhs, rhs) = Self::usual_arith_conv(lhs, node);
+                node = NodeKind::Lt { lhs, rhs, }.wrap();
                 continue;
             }
 
-            if let Ok(_) = self.cur_token().expect_symbol("<=") {
+            if self.cur_token().expect_symbol(">=").is_ok() {
                 let _ = &self.next_token();
-                node = NodeKind::Le { lhs: node, rhs: self.add()?, }.wrap();
+                let lhs = self.shift()?;
+                let (lhs, rhs) = Self::usual_arith_conv(lhs, node);
+                node = NodeKind::Le { lhs, rhs, }.wrap();
                 continue;
             }
 
-            if let Ok(_) = self.cur_token().expect_symbol(">") {
+            return Ok(node);
+        }
+    }
+
+    // the operands are promoted separately, because the type of shift is the promoted lhs
+    fn shift(&mut self) -> Result<Option<Box<Node>>, CompileError> {
+        let mut node: Option<Box<Node>> = self.add()?;
+
+        loop {
+
+            if self.cur_token().expect_symbol("<<").is_ok() {
                 let _ = &self.next_token();
-                node = NodeKind::Lt { lhs: self.add()?, rhs: node, }.wrap();
+                let mut rhs = self.add()?;
+                self.check_integer_operands(&mut node, &mut rhs, "<<")?;
+                let (lhs, rhs) = (Self::integer_promotion(node), Self::integer_promotion(rhs));
+                node = NodeKind::Shl { lhs, rhs, }.wrap();
                 continue;
             }
 
-            if let Ok(_) = self.cur_token().expect_symbol(">=") {
+            if self.cur_token().expect_symbol(">>").is_ok() {
                 let _ = &self.next_token();
-                node = NodeKind::Le { lhs: self.add()?, rhs: node, }.wrap();
+                let mut rhs = self.add()?;
+                self.check_integer_operands(&mut node, &mut rhs, ">>")?;
+                let (lhs, rhs) = (Self::integer_promotion(node), Self::integer_promotion(rhs));
+                node = NodeKind::Shr { lhs, rhs, }.wrap();
                 continue;
             }
 
@@ -565,7 +2311,7 @@ impl<'a> Parser<'a> {
 
         loop {
 
-            if let Ok(_) = self.cur_token().expect_symbol("+") {
+            if self.cur_token().expect_symbol("+").is_ok() {
                 let _ = &self.next_token();
                 //node = NodeKind::Add { lhs: node, rhs: self.mul()?, }.wrap();
                 let rhs = self.mul()?;
@@ -573,7 +2319,7 @@ impl<'a> Parser<'a> {
                 continue;
             }
 
-            if let Ok(_) = self.cur_token().expect_symbol("-") {
+            if self.cur_token().expect_symbol("-").is_ok() {
                 let _ = &self.next_token();
                 //node = NodeKind::Sub { lhs: node, rhs: self.mul()?, }.wrap();
                 let rhs = self.mul()?;
@@ -586,19 +2332,32 @@ impl<'a> Parser<'a> {
     }
 
     fn mul(&mut self) -> Result<Option<Box<Node>>, CompileError> {
-        let mut node: Option<Box<Node>> = self.unary()?;
+        let mut node: Option<Box<Node>> = self.cast()?;
 
         loop {
 
-            if let Ok(_) = self.cur_token().expect_symbol("*") {
+            if self.cur_token().expect_symbol("*").is_ok() {
+                let _ = &self.next_token();
+                let rhs = self.cast()?;
+                let (lhs, rhs) = Self::usual_arith_conv(node, rhs);
+                node = NodeKind::Mul { lhs, rhs, }.wrap();
+                continue;
+            }
+
+            if self.cur_token().expect_symbol("/").is_ok() {
                 let _ = &self.next_token();
-                node = NodeKind::Mul { lhs: node, rhs: self.unary()?, }.wrap();
+                let rhs = self.cast()?;
+                let (lhs, rhs) = Self::usual_arith_conv(node, rhs);
+                node = NodeKind::Div { lhs, rhs, }.wrap();
                 continue;
             }
 
-            if let Ok(_) = self.cur_token().expect_symbol("/") {
+            if self.cur_token().expect_symbol("%").is_ok() {
                 let _ = &self.next_token();
-                node = NodeKind::Div { lhs: node, rhs: self.unary()?, }.wrap();
+                let mut rhs = self.cast()?;
+                self.check_integer_operands(&mut node, &mut rhs, "%")?;
+                let (lhs, rhs) = Self::usual_arith_conv(node, rhs);
+                node = NodeKind::Mod { lhs, rhs, }.wrap();
                 continue;
             }
 
@@ -606,36 +2365,254 @@ impl<'a> Parser<'a> {
         }
     }
 
+    // "(" typename ")" cast | unary
+    fn cast(&mut self) -> Result<Option<Box<Node>>, CompileError> {
+        let is_type = match (self.cur_token().expect_symbol("("), self.cur_token().next.as_deref()) {
+            (Ok(_), Some(next)) => self.is_typename(next),
+            _ => false,
+        };
+        if !is_type {
+            return self.unary();
+        }
+
+        let _ = &self.next_token();
+        let ty = self.typename()?;
+        self.stmt_expect_symbol(")")?;
+
+        // compound literal (e.g. "(struct P){1, 2}.x")
+        if self.cur_token().expect_symbol("{").is_ok() {
+            let node = self.compound_literal(ty)?;
+            return self.postfix_ops(node);
+        }
+
+        // the result of cast is a value, which has no qualifiers
+        let ty = ty.unqualified().clone();
+
+        let mut lhs = self.cast()?;
+        let from = lhs.as_mut().unwrap().ty().decay();
+
+        // only between scalar types, and floating point number can't be converted to/from pointer
+        let is_scalar = |ty: &Ty| ty.is_numeric() || matches!(ty, Ty::Pointer { .. });
+        if !is_scalar(&from) || !is_scalar(&ty) || from.is_flonum() && ty.base().is_some() || from.base().is_some() && ty.is_flonum() {
+            return Err(CompileError::new(&[&format!("invalid cast from {} to {} in {}", from, ty, &self.cur_func)]));
+        }
+
+        // an integer constant which fits in the type is not warned (e.g. "(char)65")
+        let is_fit_constant = match lhs.as_deref().unwrap().kind {
+            NodeKind::Num { value } => match ty {
+                Ty::Char => (value as i8) as i32 == value,
+                Ty::UChar => (value as u8) as i32 == value,
+                _ => true,
+            },
+            _ => false,
+        };
+        if from.loses_information(&ty) && !is_fit_constant {
+            eprintln!("warning: cast from {} to {} may lose information in {}", from, ty, &self.cur_func);
+        }
+
+        Ok(NodeKind::Cast { lhs, ty }.wrap())
+    }
+
     fn unary(&mut self) -> Result<Option<Box<Node>>, CompileError> {
-        if let Ok(_) = self.cur_token().expect_symbol("+") {
+        if self.cur_token().expect_symbol("+").is_ok() {
             let _ = &self.next_token();
-            return self.unary();
+            return self.cast();
+        }
+        if self.cur_token().expect_symbol("-").is_ok() {
+            let _ = &self.next_token();
+            let lhs = Self::integer_promotion(self.cast()?);
+            return Ok(NodeKind::Neg { lhs, }.wrap());
+        }
+        if self.cur_token().expect_symbol("!").is_ok() {
+            let _ = &self.next_token();
+            return Ok(NodeKind::Not { lhs: self.cast()?, }.wrap());
+        }
+        // ++x is x += 1
+        if self.cur_token().expect_symbol("++").is_ok() {
+            let _ = &self.next_token();
+            let lhs = self.unary()?;
+            return self.new_compound_assign("+", lhs, NodeKind::Num { value: 1 }.wrap());
+        }
+        if self.cur_token().expect_symbol("--").is_ok() {
+            let _ = &self.next_token();
+            let lhs = self.unary()?;
+            return self.new_compound_assign("-", lhs, NodeKind::Num { value: 1 }.wrap());
+        }
+        if self.cur_token().expect_symbol("~").is_ok() {
+            let _ = &self.next_token();
+            let mut lhs = self.cast()?;
+            if !lhs.as_mut().unwrap().ty().is_integer() {
+                return Err(CompileError::new(&[&format!("invalid operand of ~ in {}", &self.cur_func)]));
+            }
+            let lhs = Self::integer_promotion(lhs);
+            return Ok(NodeKind::BitNot { lhs, }.wrap());
+        }
+        if self.cur_token().expect_symbol("&").is_ok() {
+            let _ = &self.next_token();
+            return Ok(NodeKind::Addr { lhs: self.cast()?, }.wrap());
         }
-        if let Ok(_) = self.cur_token().expect_symbol("-") {
+        // the address of label (there is no void, so it is char *)
+        if self.cur_token().expect_symbol("&&").is_ok() {
+            let _ = &self.next_token();
+            let name = self.cur_token().expect_ident()?.to_string();
             let _ = &self.next_token();
-            let zero = NodeKind::Num { value: 0, }.wrap();
-            return Ok(NodeKind::Sub { lhs: zero, rhs: self.unary()?, }.wrap());
+            self.goto_labels.push(name.clone());
+            return Ok(NodeKind::LabelAddr { name }.wrap());
         }
-        if let Ok(_) = self.cur_token().expect_symbol("&") {
+        if self.cur_token().expect_symbol("*").is_ok() {
             let _ = &self.next_token();
-            return Ok(NodeKind::Addr { lhs: self.unary()?, }.wrap());
+            return Ok(NodeKind::Deref { lhs: self.cast()?, }.wrap());
         }
-        if let Ok(_) = self.cur_token().expect_symbol("*") {
+        if self.cur_token().at_sizeof() {
             let _ = &self.next_token();
-            return Ok(NodeKind::Deref { lhs: self.unary()?, }.wrap());
+
+            // "sizeof" "(" typename ")"
+            self.sizeof_depth += 1;
+            let is_type = match (self.cur_token().expect_symbol("("), self.cur_token().next.as_deref()) {
+                (Ok(_), Some(next)) => self.is_typename(next),
+                _ => false,
+            };
+            let ty = match is_type {
+                true => {
+                    let _ = &self.next_token();
+                    let ty = self.typename()?;
+                    self.stmt_expect_symbol(")")?;
+
+                    // "sizeof (int[]){1, 2}" is the size of compound literal
+                    match self.cur_token().expect_symbol("{") {
+                        Ok(_) => {
+                            let node = self.compound_literal(ty)?;
+                            self.postfix_ops(node)?.as_mut().unwrap().ty().clone()
+                        },
+                        Err(_) => ty,
+                    }
+                },
+                false => self.unary()?.as_mut().unwrap().ty().clone(),
+            };
+            self.sizeof_depth -= 1;
+
+            if !ty.is_complete() {
+                return Err(CompileError::new(&[&format!("sizeof incomplete type in {}", &self.cur_func)]));
+            }
+            return Ok(NodeKind::num(ty.size()));
+        }
+
+        self.postfix()
+    }
+
+    // postfix = primary ("[" expr "]" | "(" fcall_args | "." ident | "->" ident)*
+    fn postfix(&mut self) -> Result<Option<Box<Node>>, CompileError> {
+        let node = self.primary()?;
+        self.postfix_ops(node)
+    }
+
+    // the operators after primary or compound literal
+    fn postfix_ops(&mut self, mut node: Option<Box<Node>>) -> Result<Option<Box<Node>>, CompileError> {
+        loop {
+
+            // x[y] is *(x+y)
+            if self.cur_token().expect_symbol("[").is_ok() {
+                let _ = &self.next_token();
+                let index = self.expr()?;
+                self.stmt_expect_symbol("]")?;
+                node = NodeKind::Deref { lhs: self.new_add(node, index)?, }.wrap();
+                continue;
+            }
+
+            // call through function pointer (e.g. "(*fp)(1, 2)", "fps[0](1, 2)")
+            if self.cur_token().expect_symbol("(").is_ok() {
+                let Some(func_ty) = node.as_mut().unwrap().ty().callee().cloned() else {
+                    return Err(self.cur_token().error("not a function"));
+                };
+                let _ = &self.next_token();
+                let args = self.fcall_args()?;
+                let args = self.convert_args(&func_ty, args, "function pointer")?;
+                let ret_buffer = self.new_ret_buffer(func_ty.return_ty().unwrap());
+                node = NodeKind::FuncPtrCall { func: node, args, ret_buffer }.wrap();
+                continue;
+            }
+
+            if self.cur_token().expect_symbol(".").is_ok() {
+                let _ = &self.next_token();
+                node = self.struct_ref(node)?;
+                continue;
+            }
+
+            // x->y is (*x).y
+            if self.cur_token().expect_symbol("->").is_ok() {
+                let _ = &self.next_token();
+                node = self.struct_ref(NodeKind::Deref { lhs: node, }.wrap())?;
+                continue;
+            }
+
+            if self.cur_token().expect_symbol("++").is_ok() {
+                let _ = &self.next_token();
+                node = self.new_post_inc_dec("+", node)?;
+                continue;
+            }
+
+            if self.cur_token().expect_symbol("--").is_ok() {
+                let _ = &self.next_token();
+                node = self.new_post_inc_dec("-", node)?;
+                continue;
+            }
+
+            return Ok(node);
+        }
+    }
+
+    // compound literal is an unnamed variable, which lives in the enclosing block, or in .data at file scope
+    fn compound_literal(&mut self, ty: Ty) -> Result<Option<Box<Node>>, CompileError> {
+        let init = self.var_initializer(ty)?;
+        let ty = init.ty.clone();
+        if !ty.is_complete() {
+            return Err(CompileError::new(&[&format!("compound literal has incomplete type in {}", &self.cur_func)]));
+        }
+
+        if self.scopes.len() == 1 {
+            let name = format!(".L.compound.{}", self.static_count);
+            self.static_count += 1;
+
+            let (data, relocs) = self.global_init(init)?;
+            self.globals.push(NodeKind::GlobalDef { name: name.clone(), ty: ty.clone(), data: Some(data), relocs, is_static: true }.wrap());
+            return Ok(NodeKind::Gvar { name, ty }.wrap());
+        }
+
+        let name = ".compound";
+        let offset = self.cur_func_add_hidden_local_variable(ty.clone());
+        let init = Self::local_initializer(name, offset, init);
+        let var = NodeKind::Lvar { name: name.to_string(), offset, ty }.wrap();
+        Ok(NodeKind::CompoundLiteral { init, var }.wrap())
+    }
+
+    // the member of const struct is also const
+    fn struct_ref(&mut self, mut lhs: Option<Box<Node>>) -> Result<Option<Box<Node>>, CompileError> {
+
+        let ty = lhs.as_mut().unwrap().qualified_ty().clone();
+        if !ty.is_aggregate() {
+            return Err(self.cur_token().error("not a struct nor a union"));
+        }
+        if !ty.is_complete() {
+            return Err(self.cur_token().error("member access to incomplete struct"));
         }
 
-        return self.primary();
+        let name = self.cur_token().expect_ident()?.to_string();
+        let Some(member) = ty.find_member(&name) else {
+            return Err(self.cur_token().error("no such member"));
+        };
+        let _ = &self.next_token();
+
+        Ok(NodeKind::Member { lhs, name, offset: member.offset, ty: member.ty.qualified(ty.qual()) }.wrap())
     }
 
 
     fn primary(&mut self) -> Result<Option<Box<Node>>, CompileError> {
 
-        if let Ok(_) = self.cur_token().expect_symbol("(") {
+        if self.cur_token().expect_symbol("(").is_ok() {
             let _ = &self.next_token();
             let node = self.expr()?;
 
-            let _ = self.cur_token().expect_symbol(")")?;
+            self.cur_token().expect_symbol(")")?;
             let _ = &self.next_token();
 
             return Ok(node);
@@ -645,44 +2622,415 @@ impl<'a> Parser<'a> {
             let name = name.to_string();
             let _ = &self.next_token();
 
-            match self.cur_token().expect_symbol("(") {
-                Ok(_) => {
-                    // func call
-                    return self.func_call(name);
+            if name.starts_with("__builtin_va_") {
+                return self.builtin_va(&name);
+            }
+
+            match (self.find_ident(&name), self.cur_token().expect_symbol("(")) {
+                // enum constant
+                (Some(Ident::EnumConst(value)), _) => return Ok(NodeKind::num(value)),
+                (Some(Ident::Typedef(_)), _) => {
+                    return Err(CompileError::new(&[&format!("typedef name: {} is used as a variable in {}", name, &self.cur_func)]));
                 },
-                Err(_) => {
-                    // local variable
-                    let (offset, v_ty) = self.cur_func_local_variable_offset(&name)?;
+                // local variable (if followed by "(", it is called in postfix as function pointer)
+                (Some(Ident::Variable(offset, v_ty)), _) => {
                     return Ok(NodeKind::Lvar{ name, offset, ty:v_ty }.wrap());
-                }
+                },
+                (Some(Ident::GlobalVar(label, v_ty)), _) => {
+                    return Ok(NodeKind::Gvar{ name: label, ty:v_ty }.wrap());
+                },
+                // func call
+                (_, Ok(_)) => {
+                    return self.func_call(name);
+                },
+                // function designator (e.g. "fp = add;")
+                (Some(Ident::Func(ty)), Err(_)) => {
+                    return Ok(NodeKind::FuncRef { name, ty }.wrap());
+                },
+                (None, Err(_)) => {
+                    return Err(CompileError::new(&[&format!("variable: {} is not defined in {}", name, &self.cur_func)]));
+                },
             }
         }
 
+        if let TokenKind::FNum(value, is_float) = self.cur_token().kind {
+            let ty = if is_float { Ty::Float } else { Ty::Double };
+            let _ = &self.next_token();
+            return Ok(NodeKind::FNum { value, ty }.wrap());
+        }
+
+        // string literal is an anonymous global char array
+        if let TokenKind::Str(bytes) = &self.cur_token().kind {
+            let mut data = bytes.clone();
+            data.push(0);
+            let _ = &self.next_token();
+            return Ok(self.new_string_literal(data));
+        }
+
         let n = self.cur_token().expect_number()?;
         let node = NodeKind::Num { value: n }.wrap();
         let _ = &self.next_token();
-        return Ok(node);
+        Ok(node)
+    }
+
+
+
+    // builtins for stdarg.h
+    // __builtin_va_start(ap, last), __builtin_va_arg(ap, type), __builtin_va_end(ap), __builtin_va_copy(dest, src)
+    fn builtin_va(&mut self, name: &str) -> Result<Option<Box<Node>>, CompileError> {
+
+        self.stmt_expect_symbol("(")?;
+        let ap = self.va_list_arg(name)?;
+
+        let node = match name {
+            "__builtin_va_start" => {
+                self.stmt_expect_symbol(",")?;
+                let _ = self.assign()?; // the last named parameter, which is not needed to find the variadic arguments
+
+                let Some(area_offset) = self.va_area else {
+                    return Err(CompileError::new(&[&format!("{} is used in non-variadic function {}", name, &self.cur_func)]));
+                };
+
+                // the named parameters are skipped (in registers and on stack)
+                let func_ty = &self.symbols.get(&self.cur_func).unwrap().ty;
+                let first_reg = if func_ty.return_ty().unwrap().is_memory_class() { 1 } else { 0 };
+                let layout = arg_locs(func_ty.params(), first_reg);
+                let gp_offset = layout.gp as i32 * 8;
+                let fp_offset = 6 * 8 + layout.fp as i32 * 16;
+                let overflow_offset = 16 + layout.stack_slots as i32 * 8;
+                NodeKind::VaStart { ap, gp_offset, fp_offset, overflow_offset, area_offset }.wrap()
+            },
+            "__builtin_va_arg" => {
+                self.stmt_expect_symbol(",")?;
+                let ty = self.typename()?.unqualified().clone();
+                // float is promoted to double when it is passed
+                if !ty.is_integer() && ty.base().is_none() && !matches!(ty, Ty::Double) {
+                    return Err(CompileError::new(&[&format!("{} supports only integer, pointer and double in {}", name, &self.cur_func)]));
+                }
+                NodeKind::VaArg { ap, ty }.wrap()
+            },
+            // nothing to release
+            "__builtin_va_end" => NodeKind::num(0),
+            // va_list is an array of one element, so copy the element
+            "__builtin_va_copy" => {
+                self.stmt_expect_symbol(",")?;
+                let src = self.va_list_arg(name)?;
+                NodeKind::Assign { lhs: NodeKind::Deref { lhs: ap }.wrap(), rhs: NodeKind::Deref { lhs: src }.wrap() }.wrap()
+            },
+            _ => return Err(CompileError::new(&[&format!("function: {} is not a builtin in {}", name, &self.cur_func)])),
+        };
+
+        self.stmt_expect_symbol(")")?;
+        Ok(node)
+    }
+
+    fn va_list_arg(&mut self, name: &str) -> Result<Option<Box<Node>>, CompileError> {
+        let mut ap = self.assign()?;
+        let is_va_list = match ap.as_mut().unwrap().ty().decay().base() {
+            Some(base) => base.is_compatible(self.va_list.base().unwrap()),
+            None => false,
+        };
+        if !is_va_list {
+            return Err(CompileError::new(&[&format!("{} expects va_list in {}", name, &self.cur_func)]));
+        }
+        Ok(ap)
     }
 
+    fn new_string_literal(&mut self, data: Vec<u8>) -> Option<Box<Node>> {
+        let name = format!(".L.str.{}", self.str_count);
+        self.str_count += 1;
 
+        let ty = Ty::new_array(Ty::Char, data.len() as i32);
+        self.globals.push(NodeKind::GlobalDef { name: name.clone(), ty: ty.clone(), data: Some(data), relocs: Vec::new(), is_static: true }.wrap());
+        NodeKind::Gvar { name, ty }.wrap()
+    }
 
     fn func_call(&mut self, name: String) -> Result<Option<Box<Node>>, CompileError> {
 
         let _ = &self.next_token();
 
+        let args = self.fcall_args()?;
+
+        // undeclared function is assumed to be unprototyped and return int, so arguments are promoted
+        let Some(Ident::Func(func_ty)) = self.find_ident(&name) else {
+            eprintln!("warning: function: {} is implicitly declared in {}", name, &self.cur_func);
+            let args = self.convert_args(&Ty::new_unprototyped_func(Ty::Int), args, &name)?;
+            return Ok(NodeKind::FuncCall { name, args, ty: Ty::Int, ret_buffer: None }.wrap());
+        };
+
+        let args = self.convert_args(&func_ty, args, &name)?;
+        let ty = func_ty.return_ty().unwrap().clone();
+        let ret_buffer = self.new_ret_buffer(&ty);
+        Ok(NodeKind::FuncCall { name, args, ty, ret_buffer }.wrap())
+    }
+
+    // returned struct/union is stored in the caller's buffer, and the value of call is its address
+    // (the buffer is rounded up to eightbytes, because the value in registers is stored by eightbytes)
+    fn new_ret_buffer(&mut self, r_type: &Ty) -> Option<i32> {
+        match r_type.is_aggregate() && self.is_evaluated() {
+            true => Some(self.cur_func_add_hidden_local_variable(Ty::new_array(Ty::new_pointer(Ty::Char), r_type.eightbytes() as i32))),
+            false => None,
+        }
+    }
+
+    // check the number of arguments, and convert each argument to the parameter type
+    fn convert_args(&self, func_ty: &Ty, args: Vec<Option<Box<Node>>>, func_name: &str) -> Result<Vec<Option<Box<Node>>>, CompileError> {
+
+        // the arguments to unprototyped function are promoted like variadic ones
+        let params = func_ty.params();
+        let is_wrong_count = match func_ty.is_variadic() || !func_ty.is_prototyped() {
+            true => args.len() < params.len(),
+            false => args.len() != params.len(),
+        };
+        if is_wrong_count {
+            return Err(CompileError::new(&[&format!("the number of arguments to {} is wrong in {} (expected {}, but got {})", func_name, &self.cur_func, params.len(), args.len())]));
+        }
+
+        let mut converted: Vec<Option<Box<Node>>> = Vec::new();
+        for (i, mut arg) in args.into_iter().enumerate() {
+            let arg = match params.get(i) {
+                Some(param) => {
+                    self.check_pointer_conversion(arg.as_mut().unwrap().ty(), param)?;
+                    self.new_cast_for_assign(arg, param)
+                },
+                // variadic argument is promoted (char to int, float to double), and the others are passed as they are
+                None if arg.as_mut().unwrap().ty().is_integer() => Some(Self::integer_promotion(arg)),
+                None if arg.as_mut().unwrap().ty().is_flonum() => Some(Self::new_cast(arg, &Ty::Double)),
+                None => Some(arg),
+            };
+
+            match arg {
+                Some(arg) => converted.push(arg),
+                None => return Err(CompileError::new(&[&format!("argument {} to {} has incompatible type in {}", i + 1, func_name, &self.cur_func)])),
+            }
+        }
+        Ok(converted)
+    }
+
+    // wrap by Cast if the type is different
+    fn new_cast(mut node: Option<Box<Node>>, ty: &Ty) -> Option<Box<Node>> {
+        match node.as_mut().unwrap().ty().is_compatible(ty) {
+            true => node,
+            false => NodeKind::Cast { lhs: node, ty: ty.clone() }.wrap(),
+        }
+    }
+
+    // integer narrower than int is promoted to int (e.g. char, unsigned char), and unsigned int is kept
+    fn integer_promotion(mut node: Option<Box<Node>>) -> Option<Box<Node>> {
+        match node.as_mut().unwrap().ty().clone() {
+            Ty::UInt => node,
+            ty if ty.is_integer() => Self::new_cast(node, &Ty::Int),
+            _ => node,
+        }
+    }
+
+    // convert both operands of arithmetic type to the common type (integers are promoted to int, and int is converted to unsigned int)
+    fn usual_arith_conv(mut lhs: Option<Box<Node>>, mut rhs: Option<Box<Node>>) -> (Option<Box<Node>>, Option<Box<Node>>) {
+        let lty = lhs.as_mut().unwrap().ty().clone();
+        let rty = rhs.as_mut().unwrap().ty().clone();
+        if !lty.is_numeric() || !rty.is_numeric() {
+            return (lhs, rhs);
+        }
+
+        let ty = match (lty, rty) {
+            (Ty::Double, _) | (_, Ty::Double) => Ty::Double,
+            (Ty::Float, _) | (_, Ty::Float) => Ty::Float,
+            (Ty::UInt, _) | (_, Ty::UInt) => Ty::UInt,
+            _ => Ty::Int,
+        };
+        (Self::new_cast(lhs, &ty), Self::new_cast(rhs, &ty))
+    }
+
+    // the result type of "cond ? then : els" is the common type of then and els
+    fn new_cond(&self, cond: Option<Box<Node>>, mut then: Option<Box<Node>>, mut els: Option<Box<Node>>) -> Result<Option<Box<Node>>, CompileError> {
+        let then_ty = then.as_mut().unwrap().ty().decay();
+        let els_ty = els.as_mut().unwrap().ty().decay();
+
+        let ty = match (&then_ty, &els_ty) {
+            (t, e) if t.is_numeric() && e.is_numeric() => {
+                (then, els) = Self::usual_arith_conv(then, els);
+                then.as_mut().unwrap().ty().clone()
+            },
+            (t, e) if t.is_compatible(e) => then_ty,
+            // the pointed types which differ only in qualifiers have the qualifiers of both (e.g. "const int *" and "int *" are "const int *")
+            (Ty::Pointer { base: Some(t) }, Ty::Pointer { base: Some(e) }) if t.unqualified().is_compatible(e.unqualified()) => {
+                Ty::new_pointer((**t).clone().qualified(e.qual()))
+            },
+            // null pointer constant is converted to the other pointer type
+            (t, _) if t.base().is_some() && Self::is_null_pointer_constant(&els) => {
+                els = Self::new_cast(els, &then_ty);
+                then_ty
+            },
+            (_, e) if e.base().is_some() && Self::is_null_pointer_constant(&then) => {
+                then = Self::new_cast(then, &els_ty);
+                els_ty
+            },
+            _ => return Err(CompileError::new(&[&format!("type mismatch in conditional expression in {}", &self.cur_func)])),
+        };
+
+        Ok(NodeKind::Cond { cond, then, els, ty }.wrap())
+    }
+
+    fn new_binary(&self, op: &str, lhs: Option<Box<Node>>, mut rhs: Option<Box<Node>>) -> Result<Option<Box<Node>>, CompileError> {
+        let mut lhs = lhs;
+        match op {
+            "+" => return self.new_add(lhs, rhs),
+            "-" => return self.new_sub(lhs, rhs),
+            "*" | "/" => {},
+            _ => self.check_integer_operands(&mut lhs, &mut rhs, op)?,
+        }
+
+        // the type of shift is the promoted lhs, which is not affected by rhs (e.g. "-8 >> (unsigned)1" is arithmetic shift)
+        let (lhs, rhs) = match op {
+            "<<" | ">>" => (Self::integer_promotion(lhs), Self::integer_promotion(rhs)),
+            _ => Self::usual_arith_conv(lhs, rhs),
+        };
+        let node = match op {
+            "*" => NodeKind::Mul { lhs, rhs },
+            "/" => NodeKind::Div { lhs, rhs },
+            "%" => NodeKind::Mod { lhs, rhs },
+            "&" => NodeKind::BitAnd { lhs, rhs },
+            "|" => NodeKind::BitOr { lhs, rhs },
+            "^" => NodeKind::BitXor { lhs, rhs },
+            "<<" => NodeKind::Shl { lhs, rhs },
+            _ => NodeKind::Shr { lhs, rhs },
+        };
+        Ok(node.wrap())
+    }
+
+    // a local variable which is not visible from the source, and returns the function to refer to it
+    // (no slot is needed if the code is not generated, e.g. "sizeof(x++)" in file scope)
+    fn new_hidden_lvar(&mut self, ty: Ty) -> impl Fn() -> Option<Box<Node>> + use<> {
+        let offset = match self.is_evaluated() {
+            true => self.cur_func_add_hidden_local_variable(ty.clone()),
+            false => 0,
+        };
+        move || NodeKind::Lvar { name: String::from(".hidden"), offset, ty: ty.clone() }.wrap()
+    }
+
+    // "x op= y" is "tmp = &x, *tmp = *tmp op y", so that x is evaluated only once (e.g. "*f() += 1")
+    fn new_compound_assign(&mut self, op: &str, mut lhs: Option<Box<Node>>, rhs: Option<Box<Node>>) -> Result<Option<Box<Node>>, CompileError> {
+        self.check_modifiable(&mut lhs)?;
+        let ty = lhs.as_mut().unwrap().ty().clone();
+        if !ty.is_numeric() && ty.base().is_none() || matches!(ty, Ty::Array { .. }) {
+            return Err(CompileError::new(&[&format!("invalid operand of {}= in {}", op, &self.cur_func)]));
+        }
+
+        let tmp = self.new_hidden_lvar(Ty::new_pointer(ty));
+        let addr = NodeKind::Assign { lhs: tmp(), rhs: NodeKind::Addr { lhs }.wrap() }.wrap();
+        let value = self.new_binary(op, NodeKind::Deref { lhs: tmp() }.wrap(), rhs)?;
+        let assign = Self::new_assign(NodeKind::Deref { lhs: tmp() }.wrap(), value);
+        Ok(NodeKind::Comma { lhs: addr, rhs: assign }.wrap())
+    }
+
+    // "x++" is "tmp = &x, old = *tmp, *tmp = old + 1, old" (the value before increment)
+    fn new_post_inc_dec(&mut self, op: &str, mut lhs: Option<Box<Node>>) -> Result<Option<Box<Node>>, CompileError> {
+        self.check_modifiable(&mut lhs)?;
+        let ty = lhs.as_mut().unwrap().ty().clone();
+        if !ty.is_numeric() && ty.base().is_none() || matches!(ty, Ty::Array { .. }) {
+            return Err(CompileError::new(&[&format!("invalid operand of {}{} in {}", op, op, &self.cur_func)]));
+        }
+
+        let tmp = self.new_hidden_lvar(Ty::new_pointer(ty.clone()));
+        let old = self.new_hidden_lvar(ty);
+        let addr = NodeKind::Assign { lhs: tmp(), rhs: NodeKind::Addr { lhs }.wrap() }.wrap();
+        let save = NodeKind::Assign { lhs: old(), rhs: NodeKind::Deref { lhs: tmp() }.wrap() }.wrap();
+        let value = self.new_binary(op, old(), NodeKind::Num { value: 1 }.wrap())?;
+        let assign = Self::new_assign(NodeKind::Deref { lhs: tmp() }.wrap(), value);
+
+        let node = NodeKind::Comma { lhs: assign, rhs: old() }.wrap();
+        let node = NodeKind::Comma { lhs: save, rhs: node }.wrap();
+        Ok(NodeKind::Comma { lhs: addr, rhs: node }.wrap())
+    }
+
+    // operands of bitwise, shift and % operators must be integer
+    fn check_integer_operands(&self, lhs: &mut Option<Box<Node>>, rhs: &mut Option<Box<Node>>, op: &str) -> Result<(), CompileError> {
+        if !lhs.as_mut().unwrap().ty().is_integer() || !rhs.as_mut().unwrap().ty().is_integer() {
+            return Err(CompileError::new(&[&format!("invalid operand of {} in {}", op, &self.cur_func)]));
+        }
+        Ok(())
+    }
+
+    // the left side of assignment can't be const, nor struct/union which has const member
+    fn check_modifiable(&self, lhs: &mut Option<Box<Node>>) -> Result<(), CompileError> {
+        let ty = lhs.as_mut().unwrap().qualified_ty();
+        if ty.is_const() || ty.has_const_member() {
+            return Err(CompileError::new(&[&format!("assignment of read-only location ({}) in {}", ty, &self.cur_func)]));
+        }
+        Ok(())
+    }
+
+    // pointer conversion can't discard the qualifiers of the pointed types at any level (e.g. "const int *" to "int *", "const char **" to "char **"),
+    // and the pointed types should be compatible except the qualifiers of the first level (e.g. "char **" to "const char **" is warned)
+    fn check_pointer_conversion(&self, from: &Ty, to: &Ty) -> Result<(), CompileError> {
+        let from = from.decay();
+        let (Ty::Pointer { base: Some(from_base) }, Ty::Pointer { base: Some(to_base) }) = (&from, to.unqualified()) else {
+            return Ok(());
+        };
+
+        let (mut f, mut t) = (&from, to);
+        while let (Ty::Pointer { base: Some(f_base) }, Ty::Pointer { base: Some(t_base) }) = (f.unqualified(), t.unqualified()) {
+            if !t_base.qual().contains(&f_base.qual()) {
+                return Err(CompileError::new(&[&format!("conversion from {} to {} discards qualifiers in {}", from, to, &self.cur_func)]));
+            }
+            (f, t) = (f_base, t_base);
+        }
+
+        // char * is used as generic pointer, because there is no void *
+        let is_generic = matches!(from_base.unqualified(), Ty::Char) || matches!(to_base.unqualified(), Ty::Char);
+        if !is_generic && !from_base.unqualified().is_compatible(to_base.unqualified()) {
+            eprintln!("warning: incompatible pointer types from {} to {} in {}", from, to, &self.cur_func);
+        }
+        Ok(())
+    }
+
+    // the value is converted to the type of lhs (e.g. "double d = 1;")
+    fn new_assign(mut lhs: Option<Box<Node>>, mut rhs: Option<Box<Node>>) -> Option<Box<Node>> {
+        let lty = lhs.as_mut().unwrap().ty().clone();
+        if lty.is_numeric() && rhs.as_mut().unwrap().ty().is_numeric() {
+            rhs = Self::new_cast(rhs, &lty);
+        }
+        NodeKind::Assign { lhs, rhs, }.wrap()
+    }
+
+    fn is_null_pointer_constant(node: &Option<Box<Node>>) -> bool {
+        matches!(node.as_deref().unwrap().kind, NodeKind::Num { value: 0 })
+    }
+
+    // convert the value to the type as if it is assigned (None if it can't be converted implicitly)
+    fn new_cast_for_assign(&self, mut node: Option<Box<Node>>, ty: &Ty) -> Option<Option<Box<Node>>> {
+
+        let from = node.as_mut().unwrap().ty().decay();
+        let is_null = Self::is_null_pointer_constant(&node);
+
+        match (&from, ty) {
+            (from, ty) if from.is_compatible(ty) => Some(node),
+            // e.g. int to char, int to double
+            (from, ty) if from.is_numeric() && ty.is_numeric() => Some(NodeKind::Cast { lhs: node, ty: ty.clone() }.wrap()),
+            // pointers are passed as they are (the base types are checked by check_pointer_conversion)
+            (Ty::Pointer { .. }, Ty::Pointer { .. }) => Some(node),
+            // null pointer constant
+            (from, Ty::Pointer { .. }) if from.is_integer() && is_null => Some(node),
+            _ => None,
+        }
+    }
+
+    // fcall_args = (assign ("," assign)*)? ")"
+    fn fcall_args(&mut self) -> Result<Vec<Option<Box<Node>>>, CompileError> {
+
         let mut args: Vec<Option<Box<Node>>> = Vec::new();
 
-        while let Err(_) = self.cur_token().expect_symbol(")") {
+        while self.cur_token().expect_symbol(")").is_err() {
             args.push(self.assign()?);
 
-            if let Ok(_) = self.cur_token().expect_symbol(",") {
+            if self.cur_token().expect_symbol(",").is_ok() {
                 let _ = &self.next_token();
             }
         }
 
         let _ = &self.next_token(); // skip ")"
 
-        Ok(NodeKind::FuncCall { name, args, }.wrap())
+        Ok(args)
     }
 
     fn new_add(&self, mut l: Option<Box<Node>>, mut r: Option<Box<Node>>) -> Result<Option<Box<Node>>, CompileError> {
@@ -693,17 +3041,24 @@ impl<'a> Parser<'a> {
         let rty = (*rb).ty();
 
 
-        return match (lty, rty) {
-            // int + int
-            (Ty::Int, Ty::Int) => Ok(NodeKind::Add { lhs: l, rhs: r, }.wrap()),
-            // pointer + pointer -> error
-            (Ty::Pointer {..}, Ty::Pointer {..}) => Err(CompileError::new(&[&format!("invalid operand in {}", &self.cur_func)])),
-            // pointer + int -> pointer + (int * 8)
-            (Ty::Pointer {..}, Ty::Int) => Ok(NodeKind::Add { lhs: l, rhs: NodeKind::Mul { lhs: r, rhs: NodeKind::eight(), }.wrap(), }.wrap()),
-            // int + pointer -> pointer + (int * 8) (l, r are reverse)
-            (Ty::Int, Ty::Pointer {..}) => Ok(NodeKind::Add { lhs: r, rhs: NodeKind::Mul { lhs: l, rhs: NodeKind::eight(), }.wrap(), }.wrap()),
-            // not reach here
-            //(_, _) => Err(CompileError::new(&[&format!("invalid combination of add in {}", &self.cur_func)])),
+        match (lty, rty) {
+            // number + number
+            (lty, rty) if lty.is_numeric() && rty.is_numeric() => {
+                let (lhs, rhs) = Self::usual_arith_conv(l, r);
+                Ok(NodeKind::Add { lhs, rhs, }.wrap())
+            },
+            // pointer + int -> pointer + (int * size of base)
+            (lty, rty) if lty.base().is_some() && rty.is_integer() => {
+                let size = lty.base().unwrap().size();
+                Ok(NodeKind::Add { lhs: l, rhs: NodeKind::Mul { lhs: r, rhs: NodeKind::num(size), }.wrap(), }.wrap())
+            },
+            // int + pointer -> pointer + (int * size of base) (l, r are reverse)
+            (lty, rty) if lty.is_integer() && rty.base().is_some() => {
+                let size = rty.base().unwrap().size();
+                Ok(NodeKind::Add { lhs: r, rhs: NodeKind::Mul { lhs: l, rhs: NodeKind::num(size), }.wrap(), }.wrap())
+            },
+            // pointer + pointer, struct -> error
+            (_, _) => Err(CompileError::new(&[&format!("invalid operand in {}", &self.cur_func)])),
         }
     }
 
@@ -715,21 +3070,26 @@ impl<'a> Parser<'a> {
         let rty = (*rb).ty();
 
 
-        return match (lty, rty) {
-            // int - int
-            (Ty::Int, Ty::Int) => Ok(NodeKind::Sub { lhs: l, rhs: r, }.wrap()),
+        match (lty, rty) {
+            // number - number
+            (lty, rty) if lty.is_numeric() && rty.is_numeric() => {
+                let (lhs, rhs) = Self::usual_arith_conv(l, r);
+                Ok(NodeKind::Sub { lhs, rhs, }.wrap())
+            },
             // pointer - pointer -> calc how many elements between lhs, rhs
-            (Ty::Pointer {..}, Ty::Pointer {..}) => {
+            (lty, rty) if lty.base().is_some() && rty.base().is_some() => {
+                let size = lty.base().unwrap().size();
                 let mut node = NodeKind::Sub { lhs: l, rhs: r, }.wrap();
                 node.as_mut().unwrap().ty = Some(Ty::Int);
-                Ok(NodeKind::Div { lhs: node, rhs: NodeKind::eight(), }.wrap())
-            },
-            // pointer - int -> pointer - (int * 8)
-            (Ty::Pointer {..}, Ty::Int) => Ok(NodeKind::Sub { lhs: l, rhs: NodeKind::Mul { lhs: r, rhs: NodeKind::eight(), }.wrap(), }.wrap()),
-            // int - pointer -> error
-            (Ty::Int, Ty::Pointer {..}) => Err(CompileError::new(&[&format!("invalid operand in {}", &self.cur_func)])),
-            // not reach here
-            //(_, _) => Err(CompileError::new(&[&format!("invalid combination of sub in {}", &self.cur_func)])),
+                Ok(NodeKind::Div { lhs: node, rhs: NodeKind::num(size), }.wrap())
+            },
+            // pointer - int -> pointer - (int * size of base)
+            (lty, rty) if lty.base().is_some() && rty.is_integer() => {
+                let size = lty.base().unwrap().size();
+                Ok(NodeKind::Sub { lhs: l, rhs: NodeKind::Mul { lhs: r, rhs: NodeKind::num(size), }.wrap(), }.wrap())
+            },
+            // int - pointer, struct -> error
+            (_, _) => Err(CompileError::new(&[&format!("invalid operand in {}", &self.cur_func)])),
         }
     }
 }
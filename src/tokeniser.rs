@@ -8,11 +8,22 @@ pub enum TokenKind {
     Ident(String),    // identifier
     Type(String),     // type
     Num(String),      // number
+    FNum(f64, bool),  // floating point number + whether it is float (suffix "f"), otherwise double
+    Str(Vec<u8>),     // string literal (escape sequences are already decoded, without the terminating '\0')
     Return,           // return
     If,               // if
     Else,             // else
     While,            // while
     For,              // for
+    Do,               // do
+    Break,            // break
+    Continue,         // continue
+    Switch,           // switch
+    Case,             // case
+    Default,          // default
+    Goto,             // goto
+    Sizeof,           // sizeof
+    StaticAssert,     // _Static_assert
     Eof               // the end of input
 }
 
@@ -44,26 +55,22 @@ impl Token {
 
     // check the end of eof
     pub fn at_eof(&self) -> bool {
-        match self.kind {
-            TokenKind::Eof => true,
-            _ => false
-        }
-    }
-
-    // check return
-    pub fn at_return(&self) -> bool {
-        match self.kind {
-            TokenKind::Return => true,
-            _ => false
-        }
+        matches!(self.kind, TokenKind::Eof)
     }
 
     // check else
     pub fn at_else(&self) -> bool {
-        match self.kind {
-            TokenKind::Else => true,
-            _ => false
-        }
+        matches!(self.kind, TokenKind::Else)
+    }
+
+    // check sizeof
+    pub fn at_sizeof(&self) -> bool {
+        matches!(self.kind, TokenKind::Sizeof)
+    }
+
+    // error string pointing this token
+    pub fn error(&self, message: &str) -> CompileError {
+        CompileError::new(&[&format!("{:>padding$} {}", '^', message, padding = self.loc+1)])
     }
 
     // if TokenKind is Reserved and op is expected, Ok
@@ -71,7 +78,7 @@ impl Token {
     pub fn expect_symbol(&self, op: &str) -> Result<(), CompileError> {
         match &self.kind {
             TokenKind::Reserved(val) if val == op => Ok(()),
-            _ => Err(CompileError::new(&[&format!("{:>padding$} expected {}", '^', op, padding = self.loc+1)]))
+            _ => Err(self.error(&format!("expected {}", op)))
         }
     }
 
@@ -80,7 +87,7 @@ impl Token {
     pub fn expect_ident(&self) -> Result<&str, CompileError> {
         match &self.kind {
             TokenKind::Ident(val) => Ok(val),
-            _ => Err(CompileError::new(&[&format!("{:>padding$} expected an ident", '^', padding = self.loc+1)]))
+            _ => Err(self.error("expected an ident"))
         }
     }
 
@@ -89,7 +96,7 @@ impl Token {
     pub fn expect_number(&self) -> Result<i32, CompileError> {
         match &self.kind {
             TokenKind::Num(_) => Ok(self.kind.num_val().unwrap()),
-            _ => Err(CompileError::new(&[&format!("{:>padding$} expected a number", '^', padding = self.loc+1)]))
+            _ => Err(self.error("expected a number"))
         }
     }
 
@@ -98,7 +105,7 @@ impl Token {
     pub fn expect_type(&self) -> Result<&str, CompileError> {
         match &self.kind {
             TokenKind::Type(val) => Ok(val),
-            _ => Err(CompileError::new(&[&format!("{:>padding$} expected a type", '^', padding = self.loc+1)]))
+            _ => Err(self.error("expected a type"))
         }
     }
 
@@ -109,7 +116,8 @@ pub struct TokenList {
     pub head: Option<Box<Token>>,
 }
 
-// the iterator of the TokenList
+// the iterator of the TokenList (clone it to go back to the position later)
+#[derive(Clone)]
 pub struct TokenListIterator<'a> {
     next: Option<&'a Token>,
 }
@@ -153,9 +161,8 @@ impl<'a> Iterator for TokenListIterator<'a> {
     type Item = &'a Token;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.next.map(|token| {
+        self.next.inspect(|token| {
             self.next = token.next.as_deref();
-            token
         })
     }
 
@@ -198,7 +205,7 @@ impl Tokeniser {
             if start_identifier_flag {
                 match &self.formula[i..i+1].chars().next().unwrap() {
                     ('0'..='9')|('a'..='z')|('A'..='Z')|'_' => {
-                        i = i+1;
+                        i += 1;
                         continue;
                     },
                     _ => {
@@ -209,7 +216,16 @@ impl Tokeniser {
                             "else" => token_list.push_back(Token::new(TokenKind::Else, start_loc)),
                             "while" => token_list.push_back(Token::new(TokenKind::While, start_loc)),
                             "for" => token_list.push_back(Token::new(TokenKind::For, start_loc)),
-                            "int" => token_list.push_back(Token::new(TokenKind::Type(name.to_string()), start_loc)),
+                            "do" => token_list.push_back(Token::new(TokenKind::Do, start_loc)),
+                            "break" => token_list.push_back(Token::new(TokenKind::Break, start_loc)),
+                            "continue" => token_list.push_back(Token::new(TokenKind::Continue, start_loc)),
+                            "switch" => token_list.push_back(Token::new(TokenKind::Switch, start_loc)),
+                            "case" => token_list.push_back(Token::new(TokenKind::Case, start_loc)),
+                            "default" => token_list.push_back(Token::new(TokenKind::Default, start_loc)),
+                            "goto" => token_list.push_back(Token::new(TokenKind::Goto, start_loc)),
+                            "sizeof" => token_list.push_back(Token::new(TokenKind::Sizeof, start_loc)),
+                            "_Static_assert" => token_list.push_back(Token::new(TokenKind::StaticAssert, start_loc)),
+                            "void"|"int"|"char"|"signed"|"unsigned"|"float"|"double"|"struct"|"union"|"enum"|"typedef"|"static"|"extern"|"const"|"volatile"|"restrict" => token_list.push_back(Token::new(TokenKind::Type(name.to_string()), start_loc)),
                             _ => token_list.push_back(Token::new(TokenKind::Ident(name.to_string()), start_loc)),
                         }
                         start_identifier_flag = false;
@@ -223,7 +239,7 @@ impl Tokeniser {
             if start_num_flag {
                 match &self.formula[i..i+1].chars().next().unwrap() {
                     '0'..='9' => {
-                        i = i+1;
+                        i += 1;
                         continue;
                     },
                     _ => {
@@ -235,18 +251,40 @@ impl Tokeniser {
 
             }
 
-            // empty
-            if &self.formula[i..i+1] == " " {
-                i = i+1;
+            // empty (newlines are in the output of the preprocessor)
+            if matches!(&self.formula[i..i+1], " "|"\n"|"\t"|"\r") {
+                i += 1;
+                continue;
+            }
+
+            // string literal
+            if &self.formula[i..i+1] == "\"" {
+                let (bytes, end) = self.read_string_literal(i)?;
+                token_list.push_back(Token::new(TokenKind::Str(bytes), i));
+                i = end;
+                continue;
+            }
+
+            // floating point number (e.g. "1.5", ".5", "1e3", "0x1.8p1")
+            if let Some((value, is_float, end)) = self.read_float_literal(i)? {
+                token_list.push_back(Token::new(TokenKind::FNum(value, is_float), i));
+                i = end;
+                continue;
+            }
+
+            // 3 bytes char
+            if i+3 <= len && matches!(&self.formula[i..i+3], "..."|"<<="|">>=") {
+                token_list.push_back(Token::new(TokenKind::Reserved(self.formula[i..i+3].to_string()), i));
+                i += 3;
                 continue;
             }
 
             // 2 bytes char
             if i+2 <= len {
                 match &self.formula[i..i+2] {
-                    "=="|"!="|"<="|">=" => {
+                    "=="|"!="|"<="|">="|"->"|"&&"|"||"|"<<"|">>"|"++"|"--"|"+="|"-="|"*="|"/="|"%="|"&="|"|="|"^=" => {
                         token_list.push_back(Token::new(TokenKind::Reserved(self.formula[i..i+2].to_string()), i));
-                        i = i+2;
+                        i += 2;
                         continue;
                     },
                     _ => {}
@@ -255,21 +293,21 @@ impl Tokeniser {
 
             // 1 byte char
             match &self.formula[i..i+1].chars().next().unwrap() {
-                '+'|'-'|'*'|'/'|'('|')'|'<'|'>'|'='|';'|'{'|'}'|','|'&' => {
+                '+'|'-'|'*'|'/'|'('|')'|'<'|'>'|'='|';'|'{'|'}'|','|'&'|'.'|'['|']'|'!'|'%'|'|'|'^'|'~'|'?'|':' => {
                     token_list.push_back(Token::new(TokenKind::Reserved(self.formula[i..i+1].to_string()), i));
-                    i = i+1;
+                    i += 1;
                     continue;
                 },
                 ('a'..='z')|('A'..='Z')|'_' => {
                     start_identifier_flag = true;
                     start_loc = i;
-                    i = i+1;
+                    i += 1;
                     continue;
                 },
                 '0'..='9' => {
                     start_num_flag = true;
                     start_loc = i;
-                    i = i+1;
+                    i += 1;
                     continue;
                 },
                 _ => {}
@@ -295,7 +333,134 @@ impl Tokeniser {
 
         Ok(token_list)
     }
-}
 
+    // read floating point number starting at start, returns the value, whether it is float and the position after it
+    // None if it is not a floating point number (e.g. integer)
+    fn read_float_literal(&self, start: usize) -> Result<Option<(f64, bool, usize)>, CompileError> {
+
+        let bytes = self.formula.as_bytes();
+        let is_digit = |i: usize| bytes.get(i).is_some_and(|c| c.is_ascii_digit());
+        let error = || CompileError::new(&[&format!("{:>padding$} invalid floating point number", '^', padding = start+1)]);
+
+        if !(is_digit(start) || bytes[start] == b'.' && is_digit(start + 1)) {
+            return Ok(None);
+        }
+
+        let (value, mut i) = match bytes[start] == b'0' && matches!(bytes.get(start + 1), Some(b'x' | b'X')) {
+            // hexadecimal needs binary exponent (e.g. "0x1.8p1" is 0x18 * 2^(-4) * 2^1)
+            true => {
+                let mut i = start + 2;
+                let mut mantissa = 0.0;
+                let mut exp = 0;
+                let mut seen_dot = false;
+                while let Some(&c) = bytes.get(i) {
+                    match (c, (c as char).to_digit(16)) {
+                        (b'.', _) if !seen_dot => seen_dot = true,
+                        (_, Some(d)) => {
+                            mantissa = mantissa * 16.0 + d as f64;
+                            if seen_dot {
+                                exp -= 4;
+                            }
+                        },
+                        _ => break,
+                    }
+                    i += 1;
+                }
+                if !matches!(bytes.get(i), Some(b'p' | b'P')) {
+                    return Ok(None);
+                }
+
+                let exp_start = i + 1;
+                i = exp_start;
+                if matches!(bytes.get(i), Some(b'+' | b'-')) {
+                    i += 1;
+                }
+                while is_digit(i) {
+                    i += 1;
+                }
+                let e: i32 = self.formula[exp_start..i].parse().map_err(|_| error())?;
+                (mantissa * 2f64.powi(exp + e), i)
+            },
+            // decimal needs "." or exponent
+            false => {
+                let mut i = start;
+                let mut is_real = false;
+                while is_digit(i) {
+                    i += 1;
+                }
+                if bytes.get(i) == Some(&b'.') {
+                    is_real = true;
+                    i += 1;
+                    while is_digit(i) {
+                        i += 1;
+                    }
+                }
+                if matches!(bytes.get(i), Some(b'e' | b'E')) {
+                    let mut j = i + 1;
+                    if matches!(bytes.get(j), Some(b'+' | b'-')) {
+                        j += 1;
+                    }
+                    if is_digit(j) {
+                        is_real = true;
+                        while is_digit(j) {
+                            j += 1;
+                        }
+                        i = j;
+                    }
+                }
+                if !is_real {
+                    return Ok(None);
+                }
+                (self.formula[start..i].parse::<f64>().map_err(|_| error())?, i)
+            },
+        };
 
+        let is_float = match bytes.get(i) {
+            Some(b'f' | b'F') => {
+                i += 1;
+                true
+            },
+            Some(b'l' | b'L') => return Err(CompileError::new(&[&format!("{:>padding$} long double is not supported", '^', padding = start+1)])),
+            _ => false,
+        };
+
+        Ok(Some((value, is_float, i)))
+    }
 
+    // read string literal starting with '"' at start, returns the content and the position after closing '"'
+    fn read_string_literal(&self, start: usize) -> Result<(Vec<u8>, usize), CompileError> {
+
+        let bytes = self.formula.as_bytes();
+        let mut content: Vec<u8> = Vec::new();
+
+        let mut i = start + 1;
+        loop {
+            match bytes.get(i) {
+                None => return Err(CompileError::new(&[&format!("{:>padding$} unclosed string literal", '^', padding = start+1)])),
+                Some(b'"') => return Ok((content, i + 1)),
+                Some(b'\\') => {
+                    let Some(&c) = bytes.get(i + 1) else {
+                        return Err(CompileError::new(&[&format!("{:>padding$} unclosed string literal", '^', padding = start+1)]));
+                    };
+                    content.push(match c {
+                        b'a' => 7,
+                        b'b' => 8,
+                        b't' => b'\t',
+                        b'n' => b'\n',
+                        b'v' => 11,
+                        b'f' => 12,
+                        b'r' => b'\r',
+                        b'e' => 27, // GNU extension
+                        b'0' => 0,
+                        _ => c, // e.g. \\, \", \'
+                    });
+                    i += 2;
+                },
+                Some(&c) => {
+                    content.push(c);
+                    i += 1;
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,594 @@
+use crate::cc_util::CompileError;
+
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+use std::str::FromStr;
+
+
+#[derive(Debug, Clone)]
+pub enum Ty {
+    Char,
+    Int,
+    UChar, // unsigned char
+    UInt, // unsigned int
+    Enum, // compatible with int
+    Float,
+    Double,
+    Pointer { base: Option<Box<Ty>> },
+    Array { base: Box<Ty>, len: i32 },
+    Func { return_ty: Box<Ty>, params: Vec<Ty>, is_variadic: bool, is_prototyped: bool }, // variadic function takes more arguments after params ("..."), the parameters of unprototyped one are not given ("()")
+    Struct { tag: Option<String>, layout: Rc<RefCell<StructLayout>> }, // layout is shared, so that an incomplete struct can be completed later
+    Union { tag: Option<String>, layout: Rc<RefCell<StructLayout>> },  // all members share offset 0
+    Qualified { base: Box<Ty>, qual: Qualifiers }, // base is not Qualified nor Array (qualified array is array of qualified elements)
+}
+
+// type qualifiers, which are meaningful only for lvalue (the value of "const int" variable is int)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Qualifiers {
+    pub is_const: bool,
+    pub is_volatile: bool,
+    pub is_restrict: bool,
+}
+
+impl Qualifiers {
+    pub fn is_empty(&self) -> bool {
+        !self.is_const && !self.is_volatile && !self.is_restrict
+    }
+
+    // whether this has all qualifiers of other (e.g. "const int *" can point to int, but "int *" can't point to const int)
+    pub fn contains(&self, other: &Qualifiers) -> bool {
+        (self.is_const || !other.is_const) && (self.is_volatile || !other.is_volatile) && (self.is_restrict || !other.is_restrict)
+    }
+
+    fn merge(&self, other: &Qualifiers) -> Qualifiers {
+        Qualifiers {
+            is_const: self.is_const || other.is_const,
+            is_volatile: self.is_volatile || other.is_volatile,
+            is_restrict: self.is_restrict || other.is_restrict,
+        }
+    }
+}
+
+impl fmt::Display for Qualifiers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = [(self.is_const, "const"), (self.is_volatile, "volatile"), (self.is_restrict, "restrict")]
+            .into_iter().filter(|(has, _)| *has).map(|(_, name)| name).collect();
+        write!(f, "{}", names.join(" "))
+    }
+}
+
+impl FromStr for Ty {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "char" => Ok(Ty::Char),
+            "int" => Ok(Ty::Int),
+            "float" => Ok(Ty::Float),
+            "double" => Ok(Ty::Double),
+            _ => Err(()),
+        }
+    }
+}
+
+// the type in the source (e.g. "int *", "struct s")
+impl fmt::Display for Ty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ty::Char => write!(f, "char"),
+            Ty::Int => write!(f, "int"),
+            Ty::UChar => write!(f, "unsigned char"),
+            Ty::UInt => write!(f, "unsigned int"),
+            Ty::Enum => write!(f, "enum"),
+            Ty::Float => write!(f, "float"),
+            Ty::Double => write!(f, "double"),
+            Ty::Pointer { base } => match base {
+                Some(base) => write!(f, "{} *", base),
+                None => write!(f, "pointer"),
+            },
+            Ty::Array { base, len } if *len < 0 => write!(f, "{} []", base),
+            Ty::Array { base, len } => write!(f, "{} [{}]", base, len),
+            Ty::Func { return_ty, .. } => write!(f, "{} ()", return_ty),
+            Ty::Struct { tag, .. } => write!(f, "struct {}", tag.as_deref().unwrap_or("(anonymous)")),
+            Ty::Union { tag, .. } => write!(f, "union {}", tag.as_deref().unwrap_or("(anonymous)")),
+            // the qualifiers of pointer itself follow "*" (e.g. "int * const")
+            Ty::Qualified { base, qual } if matches!(**base, Ty::Pointer { .. }) => write!(f, "{} {}", base, qual),
+            Ty::Qualified { base, qual } => write!(f, "{} {}", qual, base),
+        }
+    }
+}
+
+impl Ty {
+    pub fn new_pointer(base: Ty) -> Ty {
+        Ty::Pointer {
+            base: Some(Box::new(base))
+        }
+    }
+
+    pub fn new_array(base: Ty, len: i32) -> Ty {
+        Ty::Array {
+            base: Box::new(base),
+            len,
+        }
+    }
+
+    pub fn new_func(return_ty: Ty, params: Vec<Ty>, is_variadic: bool) -> Ty {
+        Ty::Func {
+            return_ty: Box::new(return_ty),
+            params,
+            is_variadic,
+            is_prototyped: true,
+        }
+    }
+
+    // function declared with empty parentheses (e.g. "int f();"), the arguments are not checked
+    pub fn new_unprototyped_func(return_ty: Ty) -> Ty {
+        Ty::Func {
+            return_ty: Box::new(return_ty),
+            params: Vec::new(),
+            is_variadic: false,
+            is_prototyped: false,
+        }
+    }
+
+    // create incomplete struct, members are defined by define_members
+    pub fn new_struct(tag: Option<String>) -> Ty {
+        Ty::Struct {
+            tag,
+            layout: Rc::new(RefCell::new(StructLayout::new())),
+        }
+    }
+
+    // create incomplete union, members are defined by define_members
+    pub fn new_union(tag: Option<String>) -> Ty {
+        Ty::Union {
+            tag,
+            layout: Rc::new(RefCell::new(StructLayout::new())),
+        }
+    }
+
+    // add qualifiers (e.g. "const" of "const int"), and they are given to the elements of array
+    pub fn qualified(self, qual: Qualifiers) -> Ty {
+        match self {
+            _ if qual.is_empty() => self,
+            Ty::Array { base, len } => Ty::new_array(base.qualified(qual), len),
+            Ty::Qualified { base, qual: q } => Ty::Qualified { base, qual: q.merge(&qual) },
+            _ => Ty::Qualified { base: Box::new(self), qual },
+        }
+    }
+
+    // the type without qualifiers
+    pub fn unqualified(&self) -> &Ty {
+        match self {
+            Ty::Qualified { base, .. } => base,
+            _ => self,
+        }
+    }
+
+    pub fn unqualified_mut(&mut self) -> &mut Ty {
+        match self {
+            Ty::Qualified { base, .. } => base,
+            _ => self,
+        }
+    }
+
+    // the qualifiers of this type (array has the ones of its elements)
+    pub fn qual(&self) -> Qualifiers {
+        match self {
+            Ty::Qualified { qual, .. } => *qual,
+            Ty::Array { base, .. } => base.qual(),
+            _ => Qualifiers::default(),
+        }
+    }
+
+    // the object of this type can't be modified, and it is placed in .rodata if it is global variable
+    pub fn is_const(&self) -> bool {
+        self.qual().is_const
+    }
+
+    // struct/union which has const member (recursively) can't be assigned as a whole
+    pub fn has_const_member(&self) -> bool {
+        self.members().iter().any(|m| m.ty.is_const() || m.ty.has_const_member())
+    }
+
+    pub fn size(&self) -> i32 {
+        match self {
+            Ty::Char | Ty::UChar => 1,
+            Ty::Int | Ty::UInt | Ty::Enum | Ty::Float => 4,
+            Ty::Double => 8,
+            Ty::Pointer { .. } => 8,
+            Ty::Array { base, len } => base.size() * len,
+            Ty::Func { .. } => 1, // same as gcc
+            Ty::Struct { layout, .. } | Ty::Union { layout, .. } => layout.borrow().size,
+            Ty::Qualified { base, .. } => base.size(),
+        }
+    }
+
+    pub fn align(&self) -> i32 {
+        match self {
+            Ty::Char | Ty::UChar => 1,
+            Ty::Int | Ty::UInt | Ty::Enum | Ty::Float => 4,
+            Ty::Double => 8,
+            Ty::Pointer { .. } => 8,
+            Ty::Array { base, .. } => base.align(),
+            Ty::Func { .. } => 1,
+            Ty::Struct { layout, .. } | Ty::Union { layout, .. } => layout.borrow().align,
+            Ty::Qualified { base, .. } => base.align(),
+        }
+    }
+
+    pub fn is_integer(&self) -> bool {
+        matches!(self.unqualified(), Ty::Char | Ty::Int | Ty::UChar | Ty::UInt | Ty::Enum)
+    }
+
+    // unsigned integer wraps around, and is divided, shifted and compared as non-negative number
+    pub fn is_unsigned(&self) -> bool {
+        matches!(self.unqualified(), Ty::UChar | Ty::UInt)
+    }
+
+    pub fn is_flonum(&self) -> bool {
+        matches!(self.unqualified(), Ty::Float | Ty::Double)
+    }
+
+    // arithmetic type
+    pub fn is_numeric(&self) -> bool {
+        self.is_integer() || self.is_flonum()
+    }
+
+    pub fn is_enum(&self) -> bool {
+        matches!(self.unqualified(), Ty::Enum)
+    }
+
+    pub fn is_func(&self) -> bool {
+        matches!(self.unqualified(), Ty::Func { .. })
+    }
+
+    pub fn is_array(&self) -> bool {
+        matches!(self.unqualified(), Ty::Array { .. })
+    }
+
+    pub fn is_struct(&self) -> bool {
+        matches!(self.unqualified(), Ty::Struct { .. })
+    }
+
+    pub fn is_union(&self) -> bool {
+        matches!(self.unqualified(), Ty::Union { .. })
+    }
+
+    // struct or union, which has members
+    pub fn is_aggregate(&self) -> bool {
+        self.is_struct() || self.is_union()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        match self {
+            Ty::Struct { layout, .. } | Ty::Union { layout, .. } => layout.borrow().complete,
+            // the length of array is unknown until the initializer is read (e.g. "int a[] = {1, 2};")
+            Ty::Array { base, len } => *len >= 0 && base.is_complete(),
+            Ty::Qualified { base, .. } => base.is_complete(),
+            _ => true,
+        }
+    }
+
+    // whether two declarations refer to the same type (e.g. prototype and definition of a function)
+    // struct/union is identified by its definition, not by its members
+    pub fn is_compatible(&self, other: &Ty) -> bool {
+        match (self, other) {
+            (Ty::Char, Ty::Char) | (Ty::Int, Ty::Int) | (Ty::UChar, Ty::UChar) | (Ty::UInt, Ty::UInt) | (Ty::Enum, Ty::Enum) | (Ty::Float, Ty::Float) | (Ty::Double, Ty::Double) => true,
+            (Ty::Int, Ty::Enum) | (Ty::Enum, Ty::Int) => true,
+            (Ty::Pointer { base: a }, Ty::Pointer { base: b }) => match (a, b) {
+                (Some(a), Some(b)) => a.is_compatible(b),
+                (None, None) => true,
+                _ => false,
+            },
+            // array of unknown length is compatible with any length (e.g. "extern int a[];" and "int a[3];")
+            (Ty::Array { base: a, len: m }, Ty::Array { base: b, len: n }) => (m == n || *m < 0 || *n < 0) && a.is_compatible(b),
+            (Ty::Func { return_ty: r1, params: p1, is_variadic: v1, is_prototyped: t1 }, Ty::Func { return_ty: r2, params: p2, is_variadic: v2, is_prototyped: t2 }) => {
+                // unprototyped function is compatible with the prototype whose parameters are not changed by the default argument promotions
+                // (e.g. "int f();" and "int f(int x)", but not "int f(char c)" nor "int f(int x, ...)")
+                let is_promoted = |params: &[Ty], is_variadic: bool| !is_variadic && params.iter().all(|p| !matches!(p, Ty::Char | Ty::UChar | Ty::Float));
+                r1.is_compatible(r2) && match (t1, t2) {
+                    (true, true) => v1 == v2 && p1.len() == p2.len() && p1.iter().zip(p2).all(|(a, b)| a.is_compatible(b)),
+                    (true, false) => is_promoted(p1, *v1),
+                    (false, true) => is_promoted(p2, *v2),
+                    (false, false) => true,
+                }
+            },
+            (Ty::Struct { layout: a, .. }, Ty::Struct { layout: b, .. }) | (Ty::Union { layout: a, .. }, Ty::Union { layout: b, .. }) => Rc::ptr_eq(a, b),
+            (Ty::Qualified { base: a, qual: p }, Ty::Qualified { base: b, qual: q }) => p == q && a.is_compatible(b),
+            _ => false,
+        }
+    }
+
+    // System V ABI passes struct/union larger than 16 bytes in memory (MEMORY class),
+    // and the others by eightbytes in registers
+    pub fn is_memory_class(&self) -> bool {
+        self.is_aggregate() && self.size() > 16
+    }
+
+    // the classes of eightbytes, None if MEMORY class
+    // an eightbyte is SSE class if it consists of only floating point numbers, otherwise INTEGER class
+    pub fn classes(&self) -> Option<Vec<ArgClass>> {
+        if self.is_memory_class() {
+            return None;
+        }
+        Some((0..self.eightbytes() as i32).map(|i| match self.is_only_flonum(i * 8, i * 8 + 8, 0) {
+            true => ArgClass::Sse,
+            false => ArgClass::Integer,
+        }).collect())
+    }
+
+    // whether all scalars in the range [lo, hi) are floating point numbers (offset is the position of this type)
+    fn is_only_flonum(&self, lo: i32, hi: i32, offset: i32) -> bool {
+        match self.unqualified() {
+            Ty::Struct { .. } | Ty::Union { .. } => self.members().iter().all(|m| m.ty.is_only_flonum(lo, hi, offset + m.offset)),
+            Ty::Array { base, len } => (0..*len).all(|i| base.is_only_flonum(lo, hi, offset + i * base.size())),
+            _ => offset < lo || hi <= offset || self.is_flonum(),
+        }
+    }
+
+    // the number of registers (or 8 bytes stack slots) needed to pass this type
+    pub fn eightbytes(&self) -> usize {
+        match self.is_aggregate() {
+            true => (self.size() as usize).div_ceil(8),
+            false => 1,
+        }
+    }
+
+    // the type pointed by pointer, or the element type of array
+    pub fn base(&self) -> Option<&Ty> {
+        match self.unqualified() {
+            Ty::Pointer { base } => base.as_deref(),
+            Ty::Array { base, .. } => Some(base),
+            _ => None,
+        }
+    }
+
+    // the number of elements, negative if it is unknown yet
+    pub fn array_len(&self) -> Option<i32> {
+        match self.unqualified() {
+            Ty::Array { len, .. } => Some(*len),
+            _ => None,
+        }
+    }
+
+    // array and function are converted to pointer when they are used as value (and the value has no qualifiers)
+    pub fn decay(&self) -> Ty {
+        match self.unqualified() {
+            Ty::Array { base, .. } => Ty::new_pointer((**base).clone()),
+            Ty::Func { .. } => Ty::new_pointer(self.unqualified().clone()),
+            ty => ty.clone(),
+        }
+    }
+
+    // some values of this type can't be represented by the type (e.g. int to char, double to int)
+    pub fn loses_information(&self, to: &Ty) -> bool {
+        match to {
+            _ if to.is_integer() => self.is_flonum() || self.size() > to.size(),
+            // float has 24 bits of significand
+            Ty::Float => matches!(self.unqualified(), Ty::Double) || self.is_integer() && self.size() > 2,
+            _ => false,
+        }
+    }
+
+    // the function type called through this type (function itself, or pointer to function)
+    pub fn callee(&self) -> Option<&Ty> {
+        match self.unqualified() {
+            ty @ Ty::Func { .. } => Some(ty),
+            Ty::Pointer { base: Some(base) } if base.is_func() => Some(base),
+            _ => None,
+        }
+    }
+
+    pub fn return_ty(&self) -> Option<&Ty> {
+        match self.unqualified() {
+            Ty::Func { return_ty, .. } => Some(return_ty),
+            _ => None,
+        }
+    }
+
+    pub fn is_variadic(&self) -> bool {
+        matches!(self.unqualified(), Ty::Func { is_variadic: true, .. })
+    }
+
+    // the type other than unprototyped function is regarded as prototyped
+    pub fn is_prototyped(&self) -> bool {
+        !matches!(self.unqualified(), Ty::Func { is_prototyped: false, .. })
+    }
+
+    pub fn params(&self) -> &[Ty] {
+        match self.unqualified() {
+            Ty::Func { params, .. } => params,
+            _ => &[],
+        }
+    }
+
+    pub fn members(&self) -> Vec<Member> {
+        match self.unqualified() {
+            Ty::Struct { layout, .. } | Ty::Union { layout, .. } => layout.borrow().members.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    // members of anonymous struct/union are searched as if they are members of this aggregate
+    pub fn find_member(&self, name: &str) -> Option<Member> {
+        for member in self.members() {
+            match &member.name {
+                Some(n) if n == name => return Some(member),
+                Some(_) => {},
+                None => {
+                    if let Some(inner) = member.ty.find_member(name) {
+                        return Some(Member { offset: member.offset + inner.offset, ..inner });
+                    }
+                },
+            }
+        }
+        None
+    }
+
+    // decide offsets of members, and complete the struct/union
+    // (member name is None for anonymous struct/union member)
+    pub fn define_members(&self, members: Vec<(Option<String>, Ty)>) -> Result<(), CompileError> {
+        let (kind, tag, layout) = match self {
+            Ty::Struct { tag, layout } => ("struct", tag, layout),
+            Ty::Union { tag, layout } => ("union", tag, layout),
+            _ => return Err(CompileError::new(&["members can be defined only in struct or union"])),
+        };
+        let tag = tag.as_deref().unwrap_or("(anonymous)");
+
+        if layout.borrow().complete {
+            return Err(CompileError::new(&[&format!("{}: {} is already defined", kind, tag)]));
+        }
+
+        // names which can be accessed from this aggregate, including promoted ones
+        let mut names: Vec<String> = Vec::new();
+
+        let mut offset = 0;
+        let mut size = 0;
+        let mut align = 1;
+        let mut defined: Vec<Member> = Vec::new();
+        for (name, ty) in members {
+            let label = name.as_deref().unwrap_or("(anonymous)");
+            if !ty.is_complete() {
+                return Err(CompileError::new(&[&format!("member: {} has incomplete type in {} {}", label, kind, tag)]));
+            }
+
+            let promoted = match &name {
+                Some(n) => vec![n.clone()],
+                None => ty.member_names(),
+            };
+            for n in promoted {
+                if names.contains(&n) {
+                    return Err(CompileError::new(&[&format!("member: {} is duplicated in {} {}", n, kind, tag)]));
+                }
+                names.push(n);
+            }
+
+            // union members all start at 0, and the size follows the largest member
+            let member_offset = match self.is_struct() {
+                true => align_to(offset, ty.align()),
+                false => 0,
+            };
+            offset = member_offset + ty.size();
+            size = size.max(offset);
+            align = align.max(ty.align());
+
+            defined.push(Member { name, ty, offset: member_offset });
+        }
+
+        let mut layout = layout.borrow_mut();
+        layout.members = defined;
+        layout.size = align_to(size, align);
+        layout.align = align;
+        layout.complete = true;
+        Ok(())
+    }
+
+    // all accessible member names, including members of anonymous struct/union
+    fn member_names(&self) -> Vec<String> {
+        self.members().into_iter().flat_map(|m| match m.name {
+            Some(n) => vec![n],
+            None => m.ty.member_names(),
+        }).collect()
+    }
+}
+
+
+// the layout of struct/union, calculated when the definition is closed
+pub struct StructLayout {
+    pub members: Vec<Member>,
+    pub size: i32,
+    pub align: i32,
+    pub complete: bool,
+}
+
+impl StructLayout {
+    fn new() -> StructLayout {
+        StructLayout {
+            members: Vec::new(),
+            size: 0,
+            align: 1,
+            complete: false,
+        }
+    }
+}
+
+// members can refer to its own struct through pointer, so don't print types of members (avoid infinite recursion)
+impl fmt::Debug for StructLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StructLayout")
+            .field("members", &self.members.iter().map(|m| (m.name.as_deref().unwrap_or("(anonymous)"), m.offset)).collect::<Vec<_>>())
+            .field("size", &self.size)
+            .field("align", &self.align)
+            .field("complete", &self.complete)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Member {
+    pub name: Option<String>, // None if anonymous struct/union
+    pub ty: Ty,
+    pub offset: i32,
+}
+
+
+pub fn align_to(n: i32, align: i32) -> i32 {
+    (n + align - 1) / align * align
+}
+
+
+// the number of registers to pass arguments in System V ABI
+pub const ARG_REGISTERS: usize = 6;
+pub const FP_ARG_REGISTERS: usize = 8;
+
+// the class of an eightbyte in System V ABI
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArgClass {
+    Integer, // general purpose register
+    Sse,     // vector register
+}
+
+// the index of general purpose register or vector register
+#[derive(Debug, Clone, Copy)]
+pub enum Reg {
+    Gp(usize),
+    Fp(usize),
+}
+
+// where an argument is passed (the registers for each eightbyte, or the first 8 bytes slot on stack)
+pub enum ArgLoc {
+    Regs(Vec<Reg>),
+    Stack(usize),
+}
+
+pub struct ArgLayout {
+    pub locs: Vec<ArgLoc>,
+    pub gp: usize,          // the number of used general purpose registers
+    pub fp: usize,          // the number of used vector registers
+    pub stack_slots: usize, // the number of used 8 bytes slots on stack
+}
+
+// decide where arguments are passed, starting from the register first_gp (1 if rdi has the hidden return pointer)
+// struct/union is passed on stack if it is MEMORY class or the rest of registers are not enough
+pub fn arg_locs(tys: &[Ty], first_gp: usize) -> ArgLayout {
+    let mut layout = ArgLayout { locs: Vec::new(), gp: first_gp, fp: 0, stack_slots: 0 };
+    for ty in tys {
+        let classes = ty.classes().unwrap_or_default();
+        let n_gp = classes.iter().filter(|c| **c == ArgClass::Integer).count();
+        let n_fp = classes.len() - n_gp;
+
+        if !classes.is_empty() && layout.gp + n_gp <= ARG_REGISTERS && layout.fp + n_fp <= FP_ARG_REGISTERS {
+            let regs = classes.iter().map(|class| match class {
+                ArgClass::Integer => { layout.gp += 1; Reg::Gp(layout.gp - 1) },
+                ArgClass::Sse => { layout.fp += 1; Reg::Fp(layout.fp - 1) },
+            }).collect();
+            layout.locs.push(ArgLoc::Regs(regs));
+        } else {
+            layout.locs.push(ArgLoc::Stack(layout.stack_slots));
+            layout.stack_slots += ty.eightbytes();
+        }
+    }
+    layout
+}
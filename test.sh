@@ -14,6 +14,64 @@ int sub(int x, int y) { return x-y; }
 int add6(int a, int b, int c, int d, int e, int f) {
   return a+b+c+d+e+f;
 }
+int add8(int a, int b, int c, int d, int e, int f, int g, int h) {
+  return a+b+c+d+e+f+g+h;
+}
+int sub10(int a, int b, int c, int d, int e, int f, int g, int h, int i, int j) {
+  return a-b-c-d-e-f-g-h-i-j;
+}
+int last12(int a, int b, int c, int d, int e, int f, int g, int h, int i, int j, int k, int l) {
+  return l;
+}
+int deref9(int a, int b, int c, int d, int e, int f, int g, int h, int *i) {
+  return *i;
+}
+int aligned() {
+  return (long)__builtin_frame_address(0) % 16 == 0;
+}
+int aligned7(int a, int b, int c, int d, int e, int f, int g) {
+  return (long)__builtin_frame_address(0) % 16 == 0 && g == 7;
+}
+struct S1 { char a; int b; };
+struct S2 { int a; int b; int c; };
+struct S3 { char a[3]; };
+struct S4 { int a[5]; };
+int sum_s1(struct S1 s) { return s.a + s.b; }
+int sum_s2(struct S2 s) { return s.a + s.b + s.c; }
+int sum_s3(struct S3 s) { return s.a[0] + s.a[1] + s.a[2]; }
+int sum_s4(struct S4 s) { return s.a[0] + s.a[1] + s.a[2] + s.a[3] + s.a[4]; }
+int mix_s(int a, struct S2 s, int b, struct S4 t, int c, struct S1 u) {
+  return a + s.a*2 + b*3 + t.a[4]*4 + c*5 + u.b*6;
+}
+int late_s2(int a, int b, int c, int d, int e, struct S2 s, int f) {
+  return a + e + s.a*10 + s.c*20 + f;
+}
+struct S1 make_s1(int a, int b) { struct S1 s = {a, b}; return s; }
+struct S2 make_s2(int a, int b, int c) { struct S2 s = {a, b, c}; return s; }
+struct S3 make_s3(int a, int b, int c) { struct S3 s = {{a, b, c}}; return s; }
+struct S4 make_s4(int x) { struct S4 s = {{x, x+1, x+2, x+3, x+4}}; return s; }
+int call_s2(int (*f)(struct S2), int x) { struct S2 s = {x, x+1, x+2}; return f(s); }
+int call_s4(int (*f)(int, struct S4, int), int x) { struct S4 t = {{x, x+1, x+2, x+3, x+4}}; return f(1, t, 2); }
+int call_make_s2(struct S2 (*f)(int)) { struct S2 s = f(3); return s.a + s.b*2 + s.c*4; }
+int call_make_s4(struct S4 (*f)(int)) { struct S4 t = f(2); return t.a[0] + t.a[4]*10; }
+double add_double(double x, double y) { return x+y; }
+float add_float(float x, float y) { return x+y; }
+double mix_fp(int a, double b, int c, float d) { return a + b*10 + c*100 + d*1000; }
+double sum10_double(double a, double b, double c, double d, double e, double f, double g, double h, double i, double j) {
+  return a+b+c+d+e+f+g+h+i+j;
+}
+struct F1 { float a; float b; };
+struct F2 { double a; int b; };
+struct F3 { float a; float b; float c; };
+double sum_f1(struct F1 s) { return s.a + s.b; }
+double sum_f2(struct F2 s) { return s.a + s.b; }
+double sum_f3(struct F3 s) { return s.a + s.b + s.c; }
+struct F2 make_f2(double a, int b) { struct F2 s = {a, b}; return s; }
+struct F3 make_f3(float x) { struct F3 s = {x, x*2, x*3}; return s; }
+int call_fp(double (*f)(double, float, int), double x) { return f(x, 2.5f, 3); }
+int ext_var = 7;
+int ext_arr[3] = {1, 2, 3};
+int get_ext_var() { return ext_var; }
 EOF
 
 try() {
@@ -33,6 +91,28 @@ try() {
   fi
 }
 
+tryfail() {
+  input="$1"
+
+  if ${CMD} "$input" > /dev/null 2>&1; then
+    echo "$input => compile error expected, but succeeded"
+    exit 1
+  fi
+  echo "$input => compile error"
+}
+
+# the assembly contains the lines (newlines are replaced by spaces, e.g. ".section .rodata .align 1 s:")
+tryasm() {
+  expected="$1"
+  input="$2"
+
+  if ! ${CMD} "$input" 2> /dev/null | tr '\n' ' ' | grep -q -F -- "$expected"; then
+    echo "$input => \"$expected\" expected, but not found"
+    exit 1
+  fi
+  echo "$input => $expected"
+}
+
 try 0 "int main() {return 0;}"
 try 42 "int main() {return 42;}"
 try 21 "int main() {return 5+20-4;}"
@@ -131,8 +211,819 @@ try 8 'int main() { int x=3, y=5; return x+y; }'
 try 1 'int main() { int x=3, y=5; int *z = &y; int *a; a = z - 1; return z-a; }'
 try 10 'int main() { int x=3, y=5; int *z = &y; int *a = &x; return *a+*z-*a+*z; }'
 
-try 5 'int main() { int x=3; aaa(&x); return x; } int aaa(int *y) { *y = 5; return y; }'
-try 7 'int main() { int x=3; aaa(&x); return x; } int aaa(int *y) { bbb(&y); return y; } int bbb(int **z) { **z=7; return z;}'
+try 5 'int main() { int x=3; aaa(&x); return x; } int aaa(int *y) { *y = 5; return *y; }'
+try 7 'int main() { int x=3; aaa(&x); return x; } int aaa(int *y) { bbb(&y); return *y; } int bbb(int **z) { **z=7; return **z;}'
+
+try 8 'int main() { struct { int a; int b; } x; return sizeof(x); }'
+try 16 'int main() { struct { int a; int *p; } x; return sizeof(x); }'
+try 24 'int main() { struct { int a; struct { int *p; int b; } y; } x; return sizeof(x); }'
+try 3 'int main() { struct { int a; int b; } x; x.a=3; x.b=5; return x.a; }'
+try 5 'int main() { struct { int a; int b; } x; x.a=3; x.b=5; return x.b; }'
+try 8 'int main() { struct { int a; int b; } x; x.a=3; x.b=5; return x.a+x.b; }'
+try 7 'int main() { struct { int a; struct { int b; int c; } y; } x; x.y.c=7; return x.y.c; }'
+try 6 'int main() { struct { int a; int *p; } x; int y=6; x.p=&y; return *x.p; }'
+try 4 'int main() { struct { int a; int b; } x; int *p=&x.b; x.a=3; *p=4; return x.b; }'
+try 5 'int main() { struct { int a; int b; } x; return &x.b-&x.a+4; }'
+try 8 'int main() { struct P { int a; int b; } x; struct P y; return sizeof(y); }'
+try 8 'struct P { int a; int b; }; int main() { struct P x; x.b=8; return x.b; }'
+try 9 'struct P { int a; int b; }; int main() { struct P x; struct P *p=&x; p->a=9; return x.a; }'
+try 9 'struct P { int a; int b; }; int main() { struct P x; struct P *p=&x; x.b=9; return (*p).b; }'
+try 12 'struct P { int a; int b; }; int main() { struct P x; x.a=3; x.b=4; return sum(&x)+5; } int sum(struct P *p) { return p->a+p->b; }'
+try 8 'int main() { struct P { int a; int b; } x, y; x.a=3; x.b=5; y=x; return y.a+y.b; }'
+try 9 'int main() { struct P { int a; struct { int b; int *c; } d; } x, y; int z=4; x.a=5; x.d.c=&z; y=x; return y.a+*y.d.c; }'
+try 7 'int main() { struct P { int a; int b; } x; x.a=3; x.b=4; struct P y=x; return y.a+y.b; }'
+try 3 'int main() { struct P { int a; } x, y, z; x.a=3; z=y=x; return z.a; }'
+try 16 'int main() { struct node { int val; struct node *next; } x; return sizeof(x); }'
+try 3 'int main() { struct node { int val; struct node *next; } a, b, c; a.val=1; b.val=2; c.val=3; a.next=&b; b.next=&c; return a.next->next->val; }'
+try 6 'int main() { struct node { int val; struct node *next; } a, b; a.val=1; b.val=5; a.next=&b; b.next=&a; return a.next->next->val+a.next->val; }'
+try 8 'int main() { struct P *p; return sizeof(p); }'
+try 4 'int main() { struct P *p; struct P { int a; } x; x.a=4; p=&x; return p->a; }'
+try 8 'int main() { struct T { int a; } x; { struct T { int a; int b; } y; return sizeof(y); } }'
+try 4 'int main() { struct T { int a; } x; { struct T { int a; int b; } y; } return sizeof(x); }'
+try 4 'int main() { struct T { int a; } x; { struct T *p=&x; p->a=4; } return x.a; }'
+
+try 8 'int main() { union { int a; int *p; } x; return sizeof(x); }'
+try 4 'int main() { union { int a; int b; } x; return sizeof(x); }'
+try 3 'int main() { union { int a; int b; } x; x.a=3; return x.b; }'
+try 16 'int main() { union { int a; struct { int *p; int b; } s; } x; return sizeof(x); }'
+try 1 'int main() { union { int a; struct { int *p; int b; } s; } x; return &x.a==&x.s.b-2; }'
+try 7 'int main() { union U { int a; int b; } x, y; x.a=7; y=x; return y.b; }'
+try 9 'int main() { struct { int a; union { int b; int *c; } u; } x, y; int z=9; x.a=1; x.u.c=&z; y=x; return *y.u.c; }'
+try 24 'int main() { struct { int a; union { int b; int *c; } u; int d; } x; return sizeof(x); }'
+try 5 'union U { int a; int b; }; int main() { union U x; union U *p=&x; p->a=5; return x.b; }'
+try 12 'int main() { struct { int a; struct { int b; int c; }; } x; x.a=3; x.b=4; x.c=5; return x.a+x.b+x.c; }'
+try 12 'int main() { struct { int a; struct { int b; int c; }; } x; return sizeof(x); }'
+try 8 'int main() { struct { int a; union { int b; int c; }; } x; x.b=4; return x.c+4; }'
+try 8 'int main() { struct { int a; union { int b; int c; }; } x; return sizeof(x); }'
+try 16 'int main() { union { int a; struct { int b; int *c; }; } x; return sizeof(x); }'
+try 2 'int main() { struct { int a; struct { int b; struct { int c; }; }; } x; x.c=2; return x.c; }'
+try 6 'int main() { struct { int a; struct { int b; int c; }; } x, y; x.b=2; x.c=4; y=x; return y.b+y.c; }'
+try 3 'int main() { struct { int a; struct { int b; int c; }; } x; return &x.c-&x.a+1; }'
+
+try 0 'int main() { enum { zero, one, two }; return zero; }'
+try 1 'int main() { enum { zero, one, two }; return one; }'
+try 2 'int main() { enum { zero, one, two }; return two; }'
+try 5 'int main() { enum { five=5, six, seven }; return five; }'
+try 6 'int main() { enum { five=5, six, seven }; return six; }'
+try 0 'int main() { enum { zero, five=5, three=3, four }; return zero; }'
+try 5 'int main() { enum { zero, five=5, three=3, four }; return five; }'
+try 3 'int main() { enum { zero, five=5, three=3, four }; return three; }'
+try 4 'int main() { enum { zero, five=5, three=3, four }; return four; }'
+try 4 'int main() { enum { zero, one, two } x; return sizeof(x); }'
+try 4 'int main() { enum t { zero, one, two }; enum t y; return sizeof(y); }'
+try 12 'int main() { enum { A=2, B=A*5, C=B+2, }; return C; }'
+try 3 'int main() { enum { A=1==1, B=A+A, C }; return C; }'
+try 9 'int main() { int *p; enum { A=sizeof(p)+1 }; return A; }'
+try 2 'enum color { RED, GREEN, BLUE }; int main() { enum color c=BLUE; return c; }'
+try 7 'enum { A=3, B=A+1 }; int main() { return A+B; }'
+try 1 'enum color { RED, GREEN }; int main() { enum color c=GREEN; return paint(c); } int paint(enum color c) { return c==GREEN; }'
+try 5 'enum { A=3 }; int main() { int A=5; return A; }'
+try 4 'int main() { enum { A=1 }; { enum { A=4 }; return A; } }'
+try 1 'int main() { enum { A=1 }; { enum { A=4 }; } return A; }'
+try 9 'int main() { struct { enum { X, Y } e; int a; } s; s.e=Y; s.a=8; return s.e+s.a; }'
+
+try 1 'int main() { typedef int t; t x=1; return x; }'
+try 1 'int main() { typedef struct {int a;} t; t x; x.a=1; return x.a; }'
+try 1 'int main() { typedef int t; t t1=1; return t1; }'
+try 2 'int main() { typedef struct {int a;} t; { typedef int t; } t x; x.a=2; return x.a; }'
+try 4 'typedef int t; int main() { t x; return sizeof(x); }'
+try 8 'typedef int *ip, **ipp; int main() { int x=8; ip p=&x; ipp pp=&p; return **pp; }'
+try 3 'typedef struct node node_t; struct node { int val; node_t *next; }; int main() { node_t a, b; a.val=1; b.val=2; a.next=&b; return a.val+a.next->val; }'
+try 5 'typedef int t; int main() { t x=2; { int t=3; x=x+t; } return x; }'
+try 7 'typedef int t; int main() { int t=7; return t; }'
+try 3 'typedef int t; int main() { { int t=1; } t x=3; return x; }'
+try 4 'typedef int a; typedef int b; int main() { a b=4; return b; }'
+try 9 'typedef int t; int main() { return f(9); } int f(t t) { return t; }'
+try 6 'typedef enum { A, B, C } abc; int main() { abc x=C; return x*3; }'
+try 16 'typedef struct { int a; int *b; } s_t; int main() { s_t x; return sizeof(x); }'
+try 5 'int main() { typedef int t; t typedef u; u x=5; return x; }'
+
+try 3 'int main() { int x[2]; int *y=&x; *y=3; return *x; }'
+try 3 'int main() { int x[3]; *x=3; *(x+1)=4; *(x+2)=5; return *x; }'
+try 5 'int main() { int x[3]; *x=3; *(x+1)=4; *(x+2)=5; return *(x+2); }'
+try 12 'int main() { int x[3]; return sizeof(x); }'
+try 24 'int main() { int x[2][3]; return sizeof(x); }'
+try 12 'int main() { int x[2][3]; return sizeof(*x); }'
+try 5 'int main() { int x[2][3]; int *y=x; *(y+5)=5; return x[1][2]; }'
+try 3 'int main() { int x[3]; x[0]=1; x[1]=2; x[2]=x[0]+x[1]; return x[2]; }'
+try 4 'int main() { int x[3]; x[1]=4; return 1[x]; }'
+try 6 'int main() { int x[2][3]; int i; int j; for (i=0; i<2; i=i+1) for (j=0; j<3; j=j+1) x[i][j]=i*3+j+1; return x[1][2]; }'
+try 8 'int main() { int x[4]; int *p=x+3; return p-x+5; }'
+try 24 'int main() { struct { int a[3]; int *p; } x; return sizeof(x); }'
+try 7 'int main() { struct { int a[3]; } x, y; x.a[2]=7; y=x; return y.a[2]; }'
+try 8 'int main() { enum { N=2 }; int x[N*2]; return sizeof(x)/N; }'
+try 4 'int main() { return sizeof(int); }'
+try 8 'int main() { return sizeof(int *); }'
+try 12 'int main() { return sizeof(int [3]); }'
+try 8 'int main() { return sizeof(int (*)[3]); }'
+try 24 'int main() { return sizeof(int *[3]); }'
+try 36 'int main() { return sizeof(int [3][3]); }'
+try 8 'int main() { return sizeof(int (*)(int, int)); }'
+try 16 'int main() { return sizeof(int (*[2])(int)); }'
+try 8 'int main() { return sizeof(struct { int a; int b; }); }'
+try 40 'int main() { int (*p)[10]; return sizeof(*p); }'
+try 8 'int main() { int (*p)[10]; return sizeof(p); }'
+try 7 'int main() { int a[10]; int (*p)[10]=&a; (*p)[3]=7; return a[3]; }'
+try 2 'int main() { int a[3][2]; int (*p)[2]=a; p[1][0]=2; return a[1][0]; }'
+try 1 'int main() { int a[3][2]; int (*p)[2]=a; return (p+1)-p; }'
+try 8 'int main() { int a[3][2]; int (*p)[2]=a; return (p+1)-a+7; }'
+try 7 'int add2(int x, int y) { return x+y; } int main() { int (*fp)(int, int)=add2; return fp(3, 4); }'
+try 7 'int add2(int x, int y) { return x+y; } int main() { int (*fp)(int, int)=&add2; return (*fp)(3, 4); }'
+try 1 'int add2(int x, int y) { return x+y; } int sub2(int x, int y) { return x-y; } int main() { int (*fp[2])(int, int); fp[0]=add2; fp[1]=sub2; return fp[1](fp[0](1, 2), 2); }'
+try 9 'int add2(int x, int y) { return x+y; } int apply(int (*f)(int, int), int a, int b) { return f(a, b); } int main() { return apply(add2, 4, 5); }'
+try 9 'int add2(int x, int y) { return x+y; } int apply(int f(int, int), int a, int b) { return f(a, b); } int main() { return apply(add2, 4, 5); }'
+try 6 'int sum(int a[3]) { return a[0]+a[1]+a[2]; } int main() { int x[3]; x[0]=1; x[1]=2; x[2]=3; return sum(x); }'
+try 8 'int len(int a[10]) { return sizeof(a); } int main() { int x[10]; return len(x); }'
+try 5 'typedef int (*binop)(int, int); int add2(int x, int y) { return x+y; } int main() { binop f=add2; return f(2, 3); }'
+try 3 'int ret(int x) { return x; } struct ops { int (*f)(int); }; int main() { struct ops o; o.f=ret; return o.f(3); }'
+
+try 36 'int main() { return add8(1,2,3,4,5,6,7,8); }'
+try 55 'int main() { return sub10(100,1,2,3,4,5,6,7,8,9); }'
+try 12 'int main() { return last12(1,2,3,4,5,6,7,8,9,10,11,12); }'
+try 9 'int main() { int x=9; return deref9(1,2,3,4,5,6,7,8,&x); }'
+try 63 'int main() { return add8(1,2,3,4,5,6,add8(1,2,3,4,5,6,7,8),6); }'
+try 4 'int main() { int i; int s=0; for (i=0; i<4; i=i+1) s=s+last12(1,2,3,4,5,6,7,8,9,10,11,1); return s; }'
+try 8 'int main() { return my8(1,2,3,4,5,6,7,8); } int my8(int a, int b, int c, int d, int e, int f, int g, int h) { return h; }'
+try 7 'int main() { return my8(1,2,3,4,5,6,7,8); } int my8(int a, int b, int c, int d, int e, int f, int g, int h) { return g; }'
+try 55 'int main() { return my10(1,2,3,4,5,6,7,8,9,10); } int my10(int a, int b, int c, int d, int e, int f, int g, int h, int i, int j) { return a+b+c+d+e+f+g+h+i+j; }'
+try 5 'int main() { int x=5; return my7(1,2,3,4,5,6,&x); } int my7(int a, int b, int c, int d, int e, int f, int *g) { return *g; }'
+try 12 'int my12(int a, int b, int c, int d, int e, int f, int g, int h, int i, int j, int k, int l) { return l; } int main() { int (*fp)(int,int,int,int,int,int,int,int,int,int,int,int)=my12; return fp(1,2,3,4,5,6,7,8,9,10,11,12); }'
+try 36 'int main() { return my8(my8(1,2,3,4,5,6,7,8),2,3,4,5,6,7,1); } int my8(int a, int b, int c, int d, int e, int f, int g, int h) { return a+b+c+d+e+f-g+h; }'
+
+try 1 'int main() { char x=1; return x; }'
+try 1 'int main() { char x=1; char y=2; return x; }'
+try 2 'int main() { char x=1; char y=2; return y; }'
+try 1 'int main() { char x; return sizeof(x); }'
+try 10 'int main() { char x[10]; return sizeof(x); }'
+try 1 'int main() { return sub_char(7, 3, 3); } int sub_char(char a, char b, char c) { return a-b-c; }'
+try 3 'int main() { char x[3]; x[0]=-1; x[1]=2; int y=4; return x[0]+y; }'
+try 0 'int main() { return ""[0]; }'
+try 1 'int main() { return sizeof(""); }'
+try 97 'int main() { return "abc"[0]; }'
+try 98 'int main() { return "abc"[1]; }'
+try 99 'int main() { return "abc"[2]; }'
+try 0 'int main() { return "abc"[3]; }'
+try 4 'int main() { return sizeof("abc"); }'
+try 7 'int main() { return "\a"[0]; }'
+try 10 'int main() { return "\n"[0]; }'
+try 92 'int main() { return "\\"[0]; }'
+try 34 'int main() { return "\""[0]; }'
+try 2 'int main() { return sizeof("\0"); }'
+try 3 'int main() { char *s="abc"; return strlen(s); }'
+try 5 'int main() { return strlen("hello"); }'
+try 0 'int main() { return strcmp("abc", "abc"); }'
+try 6 'int main() { return printf("hello\n"); }'
+try 8 'int main() { return 1+printf("%d %s\n", 42, "abc"); }'
+try 8 'int main() { int x=1; return x+printf("%d %s\n", 42, "abc"); }'
+try 21 'int main() { return 8+printf("%d %d %d %d %d\n", 1, 2, 3, 4, 5)+3; }'
+try 19 'int main() { return 1+printf("%d %d %d %d %d %d %d\n", 1, 2, 3, 4, 5, 6, 7)+2+2; }'
+try 22 'int main() { return printf("%d %d %d %d %d %d %d %d\n", 1, 2, 3, 4, 5, 6, 7, 8)+5+1-0; }'
+try 5 'int main() { int i; for (i=0; i<5; i=i+1) 1+printf("%d\n", i); return i; }'
+try 7 'int main() { char buf[10]; sprintf(buf, "%d%s", 7, "x"); return buf[0]-48+buf[2]; }'
+try 42 'int main() { char buf[16]; snprintf(buf, 16, "%d", 42); return atoi(buf); }'
+try 3 'int *calloc(int n, int size); int free(int *p); int main() { int *p=calloc(4, 4); p[2]=3; int r=p[2]; free(p); return r; }'
+
+try 1 'int main() { return aligned(); }'
+try 2 'int main() { return 1+aligned(); }'
+try 3 'int main() { return 1+(1+aligned()); }'
+try 1 'int main() { return aligned7(1,2,3,4,5,6,7); }'
+try 2 'int main() { return 1+aligned7(1,2,3,4,5,6,7); }'
+try 3 'int main() { return 1+(1+aligned7(1,2,3,4,5,6,7)); }'
+try 2 'int main() { return aligned7(1,2,3,4,5,6,7)+aligned7(1,2,3,4,5,6,7); }'
+try 5 'int main() { return add6(aligned(), 1+aligned(), 0, 0, 0, aligned7(1,2,3,4,5,aligned(),7)+aligned()); }'
+try 5 'int main() { int i; int n=0; for (i=0; i<5; i=i+1) n=n+aligned(); return n; }'
+try 3 'int main() { int x=1; if (x) x=x+aligned(); else x=0; return x+aligned(); }'
+try 1 'int f() { return aligned(); } int main() { return 0+f(); }'
+
+try 6 'int f(int x); int main() { return f(3); } int f(int x) { return x*2; }'
+try 3 'int add(int, int); int main() { return add(1, 2); }'
+try 2 'int main() { int sub(int, int); return sub(5, 3); }'
+try 4 'int f(int); int f(int x); int f(int x) { return x; } int main() { return f(4); }'
+try 1 'char g(int x) { return x; } int main() { return g(257); }'
+try 1 'char g() { return 1; } int main() { return sizeof(g()); }'
+try 4 'int g() { return 1; } int main() { return sizeof(g()); }'
+try 8 'int *p(int *x) { return x; } int main() { return sizeof(p(0)); }'
+try 7 'int *p(int *x) { return x; } int main() { int a=7; return *p(&a); }'
+try 2 'int h(char c) { return c; } int main() { return h(258); }'
+try 1 'int f(int *p) { return p == 0; } int main() { return f(0); }'
+try 3 'char g(int x) { return x; } int main() { char (*fp)(int) = g; return fp(259); }'
+try 3 'enum e { A, B }; int f(enum e x) { return x+2; } int main() { return f(B); }'
+tryfail 'int f(int x) { return x; } int main() { return f(1, 2); }'
+tryfail 'int f(int x) { return x; } int main() { int (*fp)(int) = f; return fp(); }'
+tryfail 'int f(int); char f(int); int main() { return 0; }'
+tryfail 'int f() { return 1; } int f() { return 2; } int main() { return 0; }'
+tryfail 'int f(int) { return 1; } int main() { return 0; }'
+tryfail 'int f(int x) { return x; } int main() { int a; return f(&a); }'
+tryfail 'int f(int *p) { return 0; } int main() { return f(1); }'
+tryfail 'struct s { int a; }; int f(int x) { return x; } int main() { struct s v; return f(v); }'
+
+try 13 'int sprintf(char *buf, char *fmt, ...); int main() { char buf[32]; return sprintf(buf, "%d,%d,%d,%d,%d,%d,%d", 1, 2, 3, 4, 5, 6, 7); }'
+try 0 'int sprintf(char *buf, char *fmt, ...); int strcmp(char *a, char *b); int main() { char buf[32]; sprintf(buf, "%d,%s,%c,%d,%d,%d,%d,%d", 1, "ab", 99, 4, 5, 6, 7, 8); return strcmp(buf, "1,ab,c,4,5,6,7,8"); }'
+try 6 'int sum(int n, ...) { __builtin_va_list ap; __builtin_va_start(ap, n); int s=0; int i; for (i=0; i<n; i=i+1) s=s+__builtin_va_arg(ap, int); __builtin_va_end(ap); return s; } int main() { return sum(3, 1, 2, 3); }'
+try 55 'int sum(int n, ...) { __builtin_va_list ap; __builtin_va_start(ap, n); int s=0; int i; for (i=0; i<n; i=i+1) s=s+__builtin_va_arg(ap, int); __builtin_va_end(ap); return s; } int main() { return sum(10, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10); }'
+try 0 'int sum(int n, ...) { __builtin_va_list ap; __builtin_va_start(ap, n); int s=0; int i; for (i=0; i<n; i=i+1) s=s+__builtin_va_arg(ap, int); __builtin_va_end(ap); return s; } int main() { return sum(0); }'
+try 7 'int sum(int n, ...) { __builtin_va_list ap; __builtin_va_start(ap, n); int s=0; int i; for (i=0; i<n; i=i+1) s=s+__builtin_va_arg(ap, int); __builtin_va_end(ap); return s; } int main() { char c=3; return sum(2, c, 4); }'
+try 5 'int sum(int n, ...) { __builtin_va_list ap; __builtin_va_start(ap, n); int s=0; int i; for (i=0; i<n; i=i+1) s=s+__builtin_va_arg(ap, int); __builtin_va_end(ap); return s; } int main() { return 1+sum(2, sum(1, 1), 3); }'
+try 7 'int nth(int n, ...) { __builtin_va_list ap; __builtin_va_start(ap, n); int *p; int i; for (i=0; i<=n; i=i+1) p=__builtin_va_arg(ap, int *); return *p; } int main() { int a=3; int b=7; return nth(1, &a, &b); }'
+try 9 'int nth(int n, ...) { __builtin_va_list ap; __builtin_va_start(ap, n); int *p; int i; for (i=0; i<=n; i=i+1) p=__builtin_va_arg(ap, int *); return *p; } int main() { int a=3; int b=9; return nth(7, &a, &a, &a, &a, &a, &a, &a, &b); }'
+try 8 'int last(int a, int b, int c, int d, int e, int f, int g, ...) { __builtin_va_list ap; __builtin_va_start(ap, g); __builtin_va_arg(ap, int); return __builtin_va_arg(ap, int); } int main() { return last(1, 2, 3, 4, 5, 6, 7, 9, 8); }'
+try 12 'int twice(int n, ...) { __builtin_va_list ap; __builtin_va_list aq; __builtin_va_start(ap, n); __builtin_va_copy(aq, ap); int s=0; int i; for (i=0; i<n; i=i+1) s=s+__builtin_va_arg(ap, int); for (i=0; i<n; i=i+1) s=s+__builtin_va_arg(aq, int); return s; } int main() { return twice(3, 1, 2, 3); }'
+try 0 'typedef __builtin_va_list va_list; int vsprintf(char *buf, char *fmt, va_list ap); int strcmp(char *a, char *b); int fmt(char *buf, char *f, ...) { va_list ap; __builtin_va_start(ap, f); int n=vsprintf(buf, f, ap); __builtin_va_end(ap); return n; } int main() { char buf[64]; fmt(buf, "%d %s %d %d %d %d %d", 1, "x", 3, 4, 5, 6, 7); return strcmp(buf, "1 x 3 4 5 6 7"); }'
+try 24 'int main() { __builtin_va_list ap; return sizeof(ap); }'
+tryfail 'int f(int n) { __builtin_va_list ap; __builtin_va_start(ap, n); return 0; } int main() { return 0; }'
+tryfail 'int f(int n, ...) { return n; } int main() { return f(); }'
+tryfail 'int f(int n, ...) { int ap; __builtin_va_start(ap, n); return 0; } int main() { return 0; }'
+tryfail 'int f(..., int n) { return n; } int main() { return 0; }'
+
+try 7 'struct S1 { char a; int b; }; int sum_s1(struct S1 s); int main() { struct S1 s; s.a=3; s.b=4; return sum_s1(s); }'
+try 6 'struct S2 { int a; int b; int c; }; int sum_s2(struct S2 s); int main() { struct S2 s; s.a=1; s.b=2; s.c=3; return sum_s2(s); }'
+try 12 'struct S3 { char a[3]; }; int sum_s3(struct S3 s); int main() { struct S3 s; s.a[0]=3; s.a[1]=4; s.a[2]=5; return sum_s3(s); }'
+try 15 'struct S4 { int a[5]; }; int sum_s4(struct S4 s); int main() { struct S4 s; int i; for (i=0; i<5; i=i+1) s.a[i]=i+1; return sum_s4(s); }'
+try 95 'struct S1 { char a; int b; }; struct S2 { int a; int b; int c; }; struct S4 { int a[5]; }; int mix_s(int a, struct S2 s, int b, struct S4 t, int c, struct S1 u); int main() { struct S1 u; struct S2 s; struct S4 t; u.b=6; s.a=2; t.a[4]=5; return mix_s(1, s, 3, t, 5, u); }'
+try 125 'struct S2 { int a; int b; int c; }; int late_s2(int a, int b, int c, int d, int e, struct S2 s, int f); int main() { struct S2 s; s.a=3; s.b=0; s.c=4; return late_s2(1, 2, 3, 4, 5, s, 9); }'
+try 23 'struct S1 { char a; int b; }; struct S1 make_s1(int a, int b); int main() { struct S1 s = make_s1(3, 20); return s.a + s.b; }'
+try 6 'struct S2 { int a; int b; int c; }; struct S2 make_s2(int a, int b, int c); int main() { struct S2 s = make_s2(1, 2, 3); return s.a + s.b + s.c; }'
+try 3 'struct S2 { int a; int b; int c; }; struct S2 make_s2(int a, int b, int c); int main() { return make_s2(1, 2, 3).c; }'
+try 12 'struct S3 { char a[3]; }; struct S3 make_s3(int a, int b, int c); int main() { struct S3 s = make_s3(3, 4, 5); return s.a[0] + s.a[1] + s.a[2]; }'
+try 6 'struct S4 { int a[5]; }; struct S4 make_s4(int x); int main() { return make_s4(2).a[4]; }'
+try 20 'struct S4 { int a[5]; }; struct S4 make_s4(int x); int sum_s4(struct S4 s); int main() { return sum_s4(make_s4(2)); }'
+try 11 'struct S2 { int a; int b; int c; }; struct S2 make_s2(int a, int b, int c); int sum_s2(struct S2 s); int main() { return 1+sum_s2(make_s2(1, 2, 3))+sum_s2(make_s2(1, 1, 2)); }'
+try 26 'struct S2 { int a; int b; int c; }; int call_s2(int (*f)(struct S2), int x); int f(struct S2 s) { return s.a*s.b + s.c; } int main() { return call_s2(f, 4); }'
+try 9 'struct S4 { int a[5]; }; int call_s4(int (*f)(int, struct S4, int), int x); int f(int a, struct S4 t, int b) { return a + t.a[1]*2 + b; } int main() { return call_s4(f, 2); }'
+try 31 'struct S2 { int a; int b; int c; }; int call_make_s2(struct S2 (*f)(int)); struct S2 g(int x) { struct S2 s; s.a=x; s.b=x+1; s.c=x+2; return s; } int main() { return call_make_s2(g); }'
+try 62 'struct S4 { int a[5]; }; int call_make_s4(struct S4 (*f)(int)); struct S4 g(int x) { struct S4 t; int i; for (i=0; i<5; i=i+1) t.a[i]=x+i; return t; } int main() { return call_make_s4(g); }'
+try 19 'struct S4 { int a[5]; }; struct S4 g(int x) { struct S4 t; int i; for (i=0; i<5; i=i+1) t.a[i]=x+i; return t; } int h(struct S4 t, int y) { return t.a[0]+t.a[4]+y; } int main() { return h(g(5), h(g(0), 1)); }'
+try 33 'struct P { char x; char y; char z; }; struct P g(char x) { struct P p; p.x=x; p.y=x+1; p.z=x+2; return p; } int main() { struct P p = g(10); return p.x+p.y+p.z; }'
+try 21 'union U { int i; char c[12]; }; union U g() { union U u; u.c[11]=21; return u; } int main() { union U u = g(); return u.c[11]; }'
+try 10 'struct S2 { int a; int b; int c; }; int f(int a, int b, int c, int d, int e, struct S2 s, int g) { return s.a + s.c + g; } int main() { struct S2 s; s.a=3; s.c=4; return f(0, 0, 0, 0, 0, s, 3); }'
+try 1 'struct S2 { int a; int b; int c; }; int sum_s2(struct S2 s); int main() { struct S2 s; s.a=1; s.b=0; s.c=0; return aligned() + sum_s2(s) - 1; }'
+tryfail 'struct A { int a; }; struct B { int a; }; int f(struct A a) { return a.a; } int main() { struct B b; return f(b); }'
+tryfail 'struct A { int a; }; struct A f() { return 1; } int main() { return 0; }'
+
+
+try 3 'int main() { double d = 3.7; return d; }'
+try 1 'int main() { return 0.1 + 0.2 > 0.3; }'
+try 1 'int main() { return 0.1f + 0.2f == 0.3f; }'
+try 12 'int main() { return sizeof(1.0) + sizeof(float); }'
+try 4 'int main() { return sizeof(1.0f); }'
+try 10 'int main() { double x = 2.5; return x * 4; }'
+try 3 'int main() { float x = 10; float y = 3; return x / y; }'
+try 253 'int main() { double x = -3.9; return x; }'
+try 7 'int main() { return 0x1.cp2; }'
+try 25 'int main() { return 2.5e1; }'
+try 1 'int main() { double x = 1e-3; return x * 1000 == 1; }'
+try 1 'int main() { double x = 1.5; return -x == -1.5; }'
+try 0 'int main() { double x = 0.0 / 0.0; return x == x; }'
+try 1 'int main() { double x = 0.0 / 0.0; return x != x; }'
+try 0 'int main() { double x = 0.0 / 0.0; return x < 1.0; }'
+try 0 'int main() { double x = 0.0 / 0.0; return x <= 1.0; }'
+try 0 'int main() { double x = 0.0 / 0.0; return x >= 1.0; }'
+try 1 'int main() { double x = 0.0 / 0.0; if (x) return 1; return 0; }'
+try 0 'int main() { double x = -0.0; if (x) return 1; return 0; }'
+try 3 'int main() { float x = 0.5; int i; for (i=0; x; i=i+1) x = x - 0.25; return i + 1; }'
+try 5 'int main() { int i = 5; double d = i; float f = d; return f; }'
+try 1 'int main() { char c = 1.9; return c; }'
+try 1 'int main() { float f = 16777217; return f == 16777216; }'
+try 1 'int main() { double a[3]; a[0]=1.5; a[1]=2.5; a[2]=a[0]+a[1]; return a[2] == 4; }'
+try 2 'struct P { char c; double d; float f; }; int main() { struct P p; p.d=1.25; p.f=2.5; return (p.d * 2 + p.f == 5) + (sizeof(p) == 24); }'
+try 12 'double add_double(double x, double y); int main() { return add_double(4.5, 7.5); }'
+try 6 'float add_float(float x, float y); int main() { return add_float(2.75, 3.25); }'
+try 123 'double mix_fp(int a, double b, int c, float d); int main() { return mix_fp(3, 2, 1, 0) + 0.5; }'
+try 55 'double sum10_double(double a, double b, double c, double d, double e, double f, double g, double h, double i, double j); int main() { return sum10_double(1, 2, 3, 4, 5, 6, 7, 8, 9, 10); }'
+try 55 'double f(double a, double b, double c, double d, double e, double f, double g, double h, double i, int x, double j) { return a+b+c+d+e+f+g+h+i+j+x-10; } int main() { return f(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 10); }'
+try 6 'float half(float x) { return x / 2; } int main() { return half(12.5) + 0.5; }'
+try 1 'int aligned7(int a, int b, int c, int d, int e, int f, int g); int main() { double x = 2.5; return aligned7(1, x, 3, 4, 5, 6, 7); }'
+try 1 'int is_one(double x) { return x == 1; } int main() { return is_one(1); }'
+try 4 'struct F1 { float a; float b; }; double sum_f1(struct F1 s); int main() { struct F1 s; s.a=1.5; s.b=2.5; return sum_f1(s); }'
+try 9 'struct F2 { double a; int b; }; double sum_f2(struct F2 s); int main() { struct F2 s; s.a=1.5; s.b=7; return sum_f2(s) + 0.5; }'
+try 12 'struct F3 { float a; float b; float c; }; double sum_f3(struct F3 s); int main() { struct F3 s; s.a=1.5; s.b=2.5; s.c=8; return sum_f3(s); }'
+try 10 'struct F2 { double a; int b; }; struct F2 make_f2(double a, int b); int main() { struct F2 s = make_f2(2.5, 7); return s.a + s.b + 0.5; }'
+try 9 'struct F3 { float a; float b; float c; }; struct F3 make_f3(float x); int main() { struct F3 s = make_f3(1.5); return s.a + s.b + s.c; }'
+try 7 'struct F3 { float a; float b; float c; }; struct F3 g(float x) { struct F3 s; s.a=x; s.b=x+1; s.c=x+2; return s; } double sum_f3(struct F3 s); int main() { return sum_f3(g(1.5)) - 0.5; }'
+try 23 'int call_fp(double (*f)(double, float, int), double x); double f(double x, float y, int z) { return x*y + z; } int main() { return call_fp(f, 8); }'
+try 0 'int sprintf(char *buf, char *fmt, ...); int strcmp(char *a, char *b); int main() { char buf[64]; float f = 0.25; sprintf(buf, "%.2f %d %.1f %d %.3f", 1.5, 2, f, 3, 2.0/3); return strcmp(buf, "1.50 2 0.2 3 0.667"); }'
+try 0 'int sprintf(char *buf, char *fmt, ...); int strcmp(char *a, char *b); int main() { char buf[128]; sprintf(buf, "%.0f %.0f %.0f %.0f %.0f %.0f %.0f %.0f %.0f %.0f", 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0); return strcmp(buf, "1 2 3 4 5 6 7 8 9 10"); }'
+try 0 'typedef __builtin_va_list va_list; int vsprintf(char *buf, char *fmt, va_list ap); int strcmp(char *a, char *b); int fmt(char *buf, char *f, ...) { va_list ap; __builtin_va_start(ap, f); int n=vsprintf(buf, f, ap); __builtin_va_end(ap); return n; } int main() { char buf[64]; fmt(buf, "%d %.1f %s %.2f", 1, 2.5, "x", 0.125); return strcmp(buf, "1 2.5 x 0.12"); }'
+try 36 'double sum(int n, ...) { __builtin_va_list ap; __builtin_va_start(ap, n); double s = 0; int i; for (i=0; i<n; i=i+1) s = s + __builtin_va_arg(ap, double); __builtin_va_end(ap); return s; } int main() { return sum(8, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0); }'
+try 55 'double sum(int n, double x, ...) { __builtin_va_list ap; __builtin_va_start(ap, x); double s = x; int i; for (i=1; i<n; i=i+1) s = s + __builtin_va_arg(ap, double); __builtin_va_end(ap); return s; } int main() { float f = 10; return sum(10, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, f); }'
+tryfail 'int main() { return 1.0L; }'
+tryfail 'int main() { __builtin_va_list ap; return __builtin_va_arg(ap, float); }'
+
+
+try 1 'int main() { return 1 && 2; }'
+try 0 'int main() { return 1 && 0; }'
+try 0 'int main() { return 0 && 1; }'
+try 1 'int main() { return 0 || 3; }'
+try 0 'int main() { return 0 || 0; }'
+try 1 'int main() { return 2 || 0; }'
+try 0 'int main() { return !1; }'
+try 1 'int main() { return !0; }'
+try 0 'int main() { return !!0; }'
+try 1 'int main() { return !!7; }'
+try 1 'int main() { int a = 0; return !a && 1 != 2; }'
+try 1 'int main() { return 1 || 0 && 0; }'
+try 0 'int main() { return (1 || 0) && 0; }'
+try 3 'int main() { int x = 3; 0 && (x = 5); return x; }'
+try 3 'int main() { int x = 3; 1 || (x = 5); return x; }'
+try 5 'int main() { int x = 3; 1 && (x = 5); return x; }'
+try 5 'int main() { int x = 3; 0 || (x = 5); return x; }'
+try 1 'int main() { int *p = 0; return !p || *p; }'
+try 1 'int main() { int a[2]; int *p = a; return p && 1; }'
+try 1 'int main() { double x = 0.0; return !x && !-0.0; }'
+try 1 'int main() { double x = 0.0 / 0.0; return x && 0.5 || 0; }'
+try 0 'int main() { float f = 0; return f || 0.0f; }'
+try 3 'enum { A = 1 && 2, B = 0 || 0, C = !0 + 1 + !5, D = 0 && 1 / 0 }; int main() { return A + B + C + D + (0 && 1 / 0); }'
+
+try 2 'int main() { return 17 % 5; }'
+try 253 'int main() { return -7 % 4; }'
+try 1 'int main() { int a = 7; int b = -3; return a % b; }'
+try 3 'int main() { return 2 + 10 % 4 * 2 - 3; }'
+try 2 'int main() { return 6 & 3; }'
+try 7 'int main() { return 6 | 3; }'
+try 5 'int main() { return 6 ^ 3; }'
+try 1 'int main() { return ~-2; }'
+try 0 'int main() { return ~-1; }'
+try 255 'int main() { char c = 0; return ~c; }'
+try 40 'int main() { return 5 << 3; }'
+try 5 'int main() { return 40 >> 3; }'
+try 1 'int main() { return -16 >> 2 == -4; }'
+try 15 'int main() { return (unsigned)-16 >> 28; }'
+try 1 'int main() { return (1 << 31) < 0; }'
+try 1 'int main() { int x = 1 << 31; return x >> 31 == -1; }'
+try 0 'int main() { return (1 << 32 >> 32) != 0 && 0; }'
+try 3 'int main() { return 1 | 2 ^ 3 & 4 == 3; }'
+try 4 'int main() { return 1 + 1 << 1; }'
+try 1 'int main() { return 16 >> 2 < 5; }'
+try 2 'int main() { return (3 & 6) | (0 ^ 0); }'
+try 1 'int main() { return 1 & 1 && 2 | 0; }'
+try 6 'int main() { int i = 0; int n = 0; for (; i < 10; i = i + 1) if (i % 3 == 0) n = n + (i & 3); return n; }'
+try 7 'enum { A = 1 << 2, B = 7 % 4, C = ~0 & 1, D = 6 ^ 5 | 8 >> 4 }; int main() { return A + B + C + D - 4; }'
+tryfail 'int main() { double x = 1.0; return x % 2; }'
+tryfail 'int main() { int a; return &a | 1; }'
+tryfail 'int main() { float f = 1; return ~f; }'
+tryfail 'enum { A = 1 % 0 }; int main() { return A; }'
+
+try 7 'int main() { int i = 2; i += 5; return i; }'
+try 3 'int main() { int i = 8; i -= 5; return i; }'
+try 24 'int main() { int i = 8; i *= 3; return i; }'
+try 4 'int main() { int i = 9; i /= 2; return i; }'
+try 1 'int main() { int i = 9; i %= 2; return i; }'
+try 2 'int main() { int i = 6; i &= 3; return i; }'
+try 7 'int main() { int i = 6; i |= 3; return i; }'
+try 5 'int main() { int i = 6; i ^= 3; return i; }'
+try 40 'int main() { int i = 5; i <<= 3; return i; }'
+try 5 'int main() { int i = 40; i >>= 3; return i; }'
+try 10 'int main() { int i = 2; int j = 3; i += j += 5; return i; }'
+try 8 'int main() { int i = 2; return (i += 3) + 3; }'
+try 4 'int main() { char c = 250; c += 10; return c; }'
+try 3 'int main() { int i = 1; i += 2.9; return i; }'
+try 1 'int main() { double d = 1.5; d *= 2; d -= 0.5; return d == 2.5; }'
+try 3 'int main() { int a[4]; a[0]=1; a[1]=2; a[2]=3; a[3]=4; int *p = a; p += 2; return *p; }'
+try 2 'int main() { int a[4]; a[0]=1; a[1]=2; a[2]=3; a[3]=4; int *p = a + 3; p -= 2; return *p; }'
+try 3 'int main() { int i = 2; return ++i; }'
+try 1 'int main() { int i = 2; return --i; }'
+try 2 'int main() { int i = 2; return i++; }'
+try 2 'int main() { int i = 2; return i--; }'
+try 3 'int main() { int i = 2; i++; return i; }'
+try 1 'int main() { int i = 2; i--; return i; }'
+try 6 'int main() { int i = 2; int j = i++; j = j + ++i; return j + i - 4; }'
+try 1 'int main() { char c = 127; c++; return c == -128; }'
+try 1 'int main() { double d = 0.1; return d++ == 0.1 && d == 1.1; }'
+try 1 'int main() { float f = 1.5; ++f; f--; return f == 1.5; }'
+try 3 'int main() { int a[4]; a[0]=1; a[1]=2; a[2]=3; a[3]=4; int *p = a; p++; ++p; return *p; }'
+try 1 'int main() { int a[4]; a[0]=1; a[1]=2; int *p = a; return *p++; }'
+try 2 'int main() { int a[4]; a[0]=1; a[1]=2; int *p = a; *p++; return *p; }'
+try 2 'int main() { int a[4]; a[0]=1; a[1]=2; int *p = a; (*p)++; return *p; }'
+try 10 'int main() { int a[3]; a[0]=0; a[1]=0; a[2]=0; int i = 0; a[i++] += 10; return a[0] + i - 1; }'
+try 45 'int main() { int i; int s = 0; for (i = 0; i < 10; i++) s += i; return s; }'
+try 31 'int *f(int *q, int *cnt) { *cnt += 1; return q; } int main() { int n = 0; int c = 0; *f(&n, &c) += 3; return n * 10 + c; }'
+try 21 'int *f(int *q, int *cnt) { *cnt += 1; return q; } int main() { int n = 1; int c = 0; (*f(&n, &c))++; return n * 10 + c; }'
+try 1 'struct P { int x; int y; }; int main() { struct P p; p.x = 1; p.y = 2; p.x += p.y; p.y--; return p.x - p.y * 2; }'
+tryfail 'int main() { return 1 += 2; }'
+tryfail 'int main() { int a[2]; a++; return 0; }'
+tryfail 'struct S { int a; }; int main() { struct S s; s += 1; return 0; }'
+tryfail 'int main() { double d = 1; d %= 2; return 0; }'
+
+try 2 'int main() { return 1 ? 2 : 3; }'
+try 3 'int main() { return 0 ? 2 : 3; }'
+try 5 'int main() { int a = 0; return a ? 1 : a + 1 ? 5 : 6; }'
+try 4 'int main() { int a = 2; return a == 1 ? 3 : a == 2 ? 4 : 5; }'
+try 7 'int main() { int a = 1; int b = 2; (a ? a : b) ; return a > b ? a : b + 5; }'
+try 3 'int main() { int x = 3; int y = 0; 1 ? x : (y = 5); return x + y; }'
+try 5 'int main() { int x = 3; int y = 0; 0 ? (x = 9) : (y = 2); return x + y; }'
+try 1 'int main() { double d = 1 ? 2 : 0.5; return d == 2; }'
+try 8 'int main() { return sizeof(1 ? 1 : 1.0); }'
+try 4 'int main() { char c = 1; return sizeof(0 ? c : c); }'
+try 3 'int main() { int a[3]; a[2] = 3; int *p = 1 ? a : 0; return p[2]; }'
+try 1 'int main() { int a; int *p = 0 ? &a : 0; return p == 0; }'
+try 1 'int main() { int a; int *p = 1 ? 0 : &a; return !p; }'
+try 2 'struct P { int x; }; int main() { struct P a; struct P b; a.x = 1; b.x = 2; struct P c = 0 ? a : b; return c.x; }'
+try 6 'int f(int x) { return x > 1 ? x * f(x - 1) : 1; } int main() { return f(3); }'
+try 3 'int main() { return (1, 2, 3); }'
+try 5 'int main() { int a = 1; int b; b = (a = 4, a + 1); return b; }'
+try 50 'int main() { int i; int j; int s = 0; for (i = 0, j = 10; i < j; i++, j--) s += i + j; return s; }'
+try 3 'int add(int x, int y); int main() { int a = 0; return add((a = 1, a + 1), 1); }'
+try 6 'int main() { int a = 1, b = 2, c = 3; return a + b + c; }'
+try 3 'enum { A = 1 ? 3 : 1 / 0 }; int main() { return A; }'
+tryfail 'struct P { int x; }; int main() { struct P a; return 1 ? a : 1; }'
+tryfail 'int main() { int a; char c; int *p = 1 ? &a : &c; return 0; }'
+tryfail 'int main() { return 1 ? 2; }'
+
+try 131 'int main() { return (char)387; }'
+try 1 'int main() { return (char)255 == -1; }'
+try 3 'int main() { return (int)3.99; }'
+try 253 'int main() { return (int)-3.99; }'
+try 1 'int main() { return (double)1 / 2 == 0.5; }'
+try 0 'int main() { return (int)(1 / 2.0) ; }'
+try 1 'int main() { return (float)0.1 != 0.1; }'
+try 1 'int main() { return (double)(float)0.5 == 0.5; }'
+try 4 'int main() { return sizeof((char)1) + sizeof((int)(char)1) - 1; }'
+try 8 'int main() { return sizeof((double)(char)1); }'
+try 1 'int main() { char c = (char)200; int i = (int)c; return i == -56; }'
+try 5 'int main() { int a[2]; a[1] = 5; int x = (int)&a[1] - (int)&a[0]; return x + 1; }'
+try 7 'int main() { int a = 7; char *p = (char *)&a; return *p; }'
+try 1 'int main() { int a = 16909060; char *p = (char *)&a; return p[3] == 1 && p[0] == 4; }'
+try 3 'int main() { int a[4]; a[3] = 3; int *p = (int *)(char *)a; return *(p + 3); }'
+try 4 'typedef int *IP; int main() { int a = 4; char *c = (char *)&a; IP p = (IP)c; return *p; }'
+try 1 'int add(int x, int y); int main() { int (*f)(int, int) = (int (*)(int, int))add; return f(3, -2); }'
+try 0 'int main() { int *p = (int *)0; return (int)p; }'
+try 2 'int main() { int x = 5; return -(int)(char)-2 + 0 * x; }'
+try 5 'int main() { double d = 2.5; return (int)d * 2 + (int)(d * 0.5); }'
+try 1 'enum { A = (char)257 }; int main() { return A; }'
+try 0 'int main() { int *p = 0; return !(p == (int *)0) + (p != 0); }'
+tryfail 'int main() { int *p; return (double)p; }'
+tryfail 'int main() { double d; return (int *)d; }'
+tryfail 'struct S { int a; }; int main() { struct S s; return (int)s; }'
+tryfail 'struct S { int a; }; int main() { int x = 1; struct S s = (struct S)x; return 0; }'
+tryfail 'int main() { int x; (int)x = 1; return 0; }'
+
+try 3 'int main() { int i = 0; for (;;) { if (i == 3) break; i++; } return i; }'
+try 5 'int main() { int i = 0; while (1) { i++; if (i >= 5) break; } return i; }'
+try 25 'int main() { int i; int s = 0; for (i = 0; i < 10; i++) { if (i % 2 == 0) continue; s += i; } return s; }'
+try 25 'int main() { int i = 0; int s = 0; while (i < 10) { i++; if (i % 2 == 0) continue; s += i; } return s; }'
+try 10 'int main() { int i = 0; do { i++; } while (i < 10); return i; }'
+try 1 'int main() { int i = 0; do i++; while (0); return i; }'
+try 7 'int main() { int i = 0; do { i++; if (i == 7) break; } while (1); return i; }'
+try 20 'int main() { int i = 0; int s = 0; do { i++; if (i % 2) continue; s += i; } while (i < 8); return s; }'
+try 12 'int main() { int i; int j; int n = 0; for (i = 0; i < 4; i++) { for (j = 0; j < 10; j++) { if (j == 3) break; n++; } } return n; }'
+try 8 'int main() { int i; int j; int n = 0; for (i = 0; i < 4; i++) { if (i == 2) continue; for (j = 0; j < 4; j++) { if (j % 2) continue; n += 2; } } return n - 4; }'
+try 4 'int main() { int i = 0; for (;; i++) { do { break; } while (1); if (i == 4) break; } return i; }'
+tryfail 'int main() { break; return 0; }'
+tryfail 'int main() { continue; return 0; }'
+tryfail 'int main() { if (1) break; return 0; }'
+tryfail 'int main() { do { } while (1) return 0; }'
+
+try 5 'int main() { int x = 2; switch (x) { case 1: return 3; case 2: return 5; case 3: return 7; } return 0; }'
+try 9 'int main() { int x = 4; switch (x) { case 1: return 3; case 2: return 5; default: return 9; } return 0; }'
+try 0 'int main() { int x = 4; switch (x) { case 1: return 3; case 2: return 5; } return 0; }'
+try 6 'int main() { int x = 1; int n = 0; switch (x) { case 0: n += 1; case 1: n += 2; case 2: n += 4; break; case 3: n += 8; } return n; }'
+try 8 'int main() { int x = 7; int n = 0; switch (x) { default: n += 8; break; case 1: n += 1; } return n; }'
+try 12 'int main() { int x = 7; int n = 0; switch (x) { default: n += 8; case 1: n += 4; } return n; }'
+try 2 'int main() { int x = -3; switch (x) { case -3: return 2; case 3: return 4; } return 0; }'
+try 1 'int main() { char c = 65; switch (c) { case 65: return 1; } return 0; }'
+try 3 'int main() { switch (3) { case 1 + 2: return 3; } return 0; }'
+try 3 'enum { A, B, C, D }; int main() { int x = D; switch (x) { case A: return 0; case B: return 1; case C: return 2; case D: return 3; } return 9; }'
+try 87 'int f(int x) { switch (x) { case 0: return 10; case 1: return 11; case 2: return 12; case 3: return 13; case 4: return 14; case 5: return 15; case 7: return 17; default: return 99; } } int main() { int i; int s = 0; for (i = -2; i < 10; i++) s += f(i); return s - 500; }'
+try 40 'int f(int x) { switch (x) { case 100: return 1; case -50: return 2; case 3000: return 3; case 7: return 4; case 999999: return 5; case -1000000: return 6; case 42: return 7; default: return 0; } } int main() { return f(100) + f(-50) * 10 - f(3000) + f(7) + f(999999) + f(-1000000) + f(42) - f(43) - f(8); }'
+try 1 'int main() { int x = 15; switch (x) { case 10: case 11: case 12: case 13: case 14: return 0; case 15: case 16: return 1; case 17: return 2; } return 3; }'
+try 11 'int main() { int i; int n = 0; for (i = 0; i < 6; i++) { switch (i) { case 1: continue; case 3: break; default: n += i; } } return n; }'
+try 6 'int main() { int i; int n = 0; for (i = 0; i < 10; i++) { switch (i % 3) { case 0: n++; break; case 1: switch (i) { case 4: n += 3; break; default: break; } break; } if (i == 8) break; } return n; }'
+try 4 'int main() { int x = 2; int n = 0; switch (x) { case 1: { case 2: n = 4; } } return n; }'
+try 7 'int main() { int x = 0; switch (x) ; switch (x) { } return 7; }'
+tryfail 'int main() { case 1: return 0; }'
+tryfail 'int main() { default: return 0; }'
+tryfail 'int main() { int x = 1; switch (x) { case 1: case 1: return 0; } return 0; }'
+tryfail 'int main() { int x = 1; switch (x) { case 1: case 2 - 1: return 0; } return 0; }'
+tryfail 'int main() { int x = 1; switch (x) { default: default: return 0; } return 0; }'
+tryfail 'int main() { int x = 1; switch (x) { case x: return 0; } return 0; }'
+tryfail 'int main() { double d = 1; switch (d) { case 1: return 0; } return 0; }'
+tryfail 'int main() { int x = 1; switch (x) { case 1: continue; } return 0; }'
+
+try 3 'int main() { int i = 0; goto a; a: i++; b: i++; c: i++; return i; }'
+try 2 'int main() { int i = 0; goto e; d: i++; e: i++; f: i++; return i; }'
+try 1 'int main() { int i = 0; goto i; g: i++; h: i++; i: i++; return i; }'
+try 10 'int main() { int i = 0; loop: if (i < 10) { i++; goto loop; } return i; }'
+try 7 'int main() { int x = 0; goto end; x = 5; end: return x + 7; }'
+try 5 'int f() { goto a; a: return 2; } int g() { goto a; a: return 3; } int main() { return f() + g(); }'
+try 4 'int main() { int i = 0; int x = 0; { a: x++; } if (++i < 4) goto a; return x; }'
+try 6 'int main() { int state = 0; int n = 0; s0: n++; if (state++ < 2) goto s1; goto done; s1: n++; goto s0; done: return n + 1; }'
+try 3 'int main() { char *p = &&b; goto *p; a: return 1; b: return 3; }'
+try 42 'int main() { char *ops[3]; ops[0] = &&inc; ops[1] = &&dbl; ops[2] = &&halt; int code[5]; code[0] = 0; code[1] = 1; code[2] = 0; code[3] = 1; code[4] = 2; int pc = 0; int acc = 9; goto *ops[code[pc]]; inc: acc++; goto *ops[code[++pc]]; dbl: acc *= 2; goto *ops[code[++pc]]; halt: return acc; }'
+try 1 'int main() { int x = 0; return &&a != &&b; a: x = 1; b: return x; }'
+try 1 'int main() { int x = 0; switch (1) { case 1: goto out; } x = 5; out: return x + 1; }'
+tryfail 'int main() { goto nowhere; return 0; }'
+tryfail 'int main() { a: a: return 0; }'
+tryfail 'int main() { char *p = &&missing; return 0; }'
+tryfail 'int f() { a: return 0; } int main() { goto a; return 0; }'
+tryfail 'int main() { int x = 1; goto *x; return 0; }'
+
+try 3 'int main() { { int a = 1; } { int a = 3; return a; } }'
+try 2 'int main() { int a = 2; { int a = 5; } return a; }'
+try 5 'int main() { int a = 2; { int a = 5; return a; } }'
+try 7 'int main() { int a = 2; { int b = a + 5; int a = b; return a; } }'
+try 12 'int main() { int x = 1; { int x = 2; { int x = 3; x = x * 4; return x; } } }'
+try 1 'int main() { int x = 1; { x = 2; int x = 3; } return x == 2; }'
+try 1 'int main() { char *p; char *q; { int a; p = (char *)&a; } { int b; q = (char *)&b; } return p == q; }'
+try 1 'int main() { char *p; char *q; { int a; p = (char *)&a; { int c; q = (char *)&c; } } return p != q; }'
+try 6 'int main() { int i; int s = 0; for (i = 0; i < 4; i++) { int t = i; s += t; } return s; }'
+try 7 'int main() { typedef int T; T a = 1; { typedef char T; T b = 2; a += sizeof(T) + b - 1; } T c = a; return c + sizeof(T); }'
+try 8 'struct S { int a; }; int main() { { struct S { int a; int b; }; struct S s; if (sizeof(s) != 8) return 0; } struct S t; return sizeof(t) + 4; }'
+try 2 'int f(int a) { { int a = 2; return a; } } int main() { return f(1); }'
+tryfail 'int main() { int a; int a; return 0; }'
+tryfail 'int f(int a) { int a; return 0; } int main() { return 0; }'
+tryfail 'int main() { { int a = 1; } return a; }'
+
+try 6 'int main() { int a[3] = {1, 2, 3}; return a[0] + a[1] + a[2]; }'
+try 0 'int main() { int a[3] = {1}; return a[1] + a[2]; }'
+try 3 'int main() { int x = 5; int a[3] = {x - 4, x - 3}; return a[0] + a[1] + a[2]; }'
+try 12 'int main() { int a[] = {3, 4, 5}; return sizeof(a) + a[0] - a[2] + 2; }'
+try 2 'int main() { int a[2] = {1, 2,}; return a[1]; }'
+try 10 'int main() { int a[2][3] = {{1, 2, 3}, {4}}; return a[0][0] + a[0][1] + a[0][2] + a[1][0] + a[1][1] + a[1][2]; }'
+try 10 'int main() { int a[2][2] = {1, 2, 3, 4}; return a[0][0] + a[0][1] + a[1][0] + a[1][1]; }'
+try 3 'int main() { int a[][2] = {1, 2, 3}; return sizeof(a) / sizeof(a[0]) + a[1][1] + 1; }'
+try 7 'int main() { int i; int s = 0; for (i = 0; i < 3; i++) { int a[3] = {i}; s += a[0] + a[1] + a[2]; a[1] = 2; } return s + 4; }'
+try 3 'int main() { int x = {3}; return x; }'
+try 3 'int main() { char s[] = "abc"; return sizeof(s) - 1; }'
+try 98 'int main() { char s[] = "abc"; return s[1]; }'
+try 0 'int main() { char s[8] = "ab"; return s[2] + s[7]; }'
+try 99 'int main() { char s[3] = "abc"; return s[2]; }'
+try 6 'int main() { char s[2][4] = {"ab", "cde"}; return s[1][2] - s[0][1] + 3; }'
+try 21 'int main() { struct { int a; char b; int c[2]; } x = {1, 2, {3, 15}}; return x.a + x.b + x.c[0] + x.c[1]; }'
+try 3 'int main() { struct { int a; int b; int c; } x = {1, 2}; return x.a + x.b + x.c; }'
+try 10 'struct P { int x; int y; }; int main() { struct P ps[2] = {1, 2, 3, 4}; return ps[0].x + ps[0].y + ps[1].x + ps[1].y; }'
+try 10 'struct P { int x; int y; }; int main() { struct P ps[] = {{1, 2}, {3, 4}}; return ps[0].x + ps[0].y + ps[1].x + ps[1].y; }'
+try 7 'struct P { int x; int y; }; int main() { struct P p = {3, 4}; struct P q = p; return q.x + q.y; }'
+try 7 'struct P { int x; int y; }; struct Q { struct P p; int z; }; int main() { struct P p = {3, 4}; struct Q q = {p, 0}; return q.p.x + q.p.y + q.z; }'
+try 4 'int main() { union { int a; char b[4]; } u = {4}; return u.b[0]; }'
+try 3 'int main() { double a[2] = {1.5, 1}; float f = 0.5; return a[0] + a[1] + f; }'
+try 1 'int main() { int a[2] = {1, 2, 3}; return a[0]; }'
+tryfail 'int main() { int a[3] = 1; return 0; }'
+tryfail 'int main() { int a[]; return 0; }'
+
+try 3 'int x = 3; int main() { return x; }'
+try 0 'int x; int main() { return x; }'
+try 5 'int x; int y[3]; int main() { x = 2; y[2] = 3; return x + y[2] + y[0]; }'
+try 9 'int x = 3, *p, y = 5; int main() { return x + y + (p == 0); }'
+try 15 'int a[5] = {1, 2, 3, 4, 5}; int main() { return a[0] + a[1] + a[2] + a[3] + a[4]; }'
+try 6 'int a[] = {1, 2, 3}; int main() { return sizeof(a) / sizeof(a[0]) + a[2]; }'
+try 0 'int a[4] = {1}; int main() { return a[1] + a[2] + a[3]; }'
+try 10 'int a[2][2] = {{1, 2}, 3, 4}; int main() { return a[0][0] + a[0][1] + a[1][0] + a[1][1]; }'
+try 104 'char s[] = "hello"; int main() { return s[0]; }'
+try 6 'char s[] = "hello"; int main() { return sizeof(s); }'
+try 0 'char s[10] = "hi"; int main() { return s[5]; }'
+try 11 'struct P { char c; int x; double d; } p = {1, 2, 8.5}; int main() { return p.c + p.x + p.d; }'
+try 10 'struct P { int x; int y; } ps[2] = {1, 2, 3, 4}; int main() { return ps[0].x + ps[0].y + ps[1].x + ps[1].y; }'
+try 44 'char c = 300; int main() { return c; }'
+try 4 'enum { A = 2, B }; int x = A * B - (1 < 2) - 1; int main() { return x; }'
+try 7 'double d = 1.5 * 3 + 2.5; float f = 3 / 2; int i = 2.9; int main() { return d + f + i - 3; }'
+try 1 'double d = -1.0 / 3; int main() { return d == -1.0 / 3; }'
+try 6 'int x = 1; int f() { x = x + 2; return x; } int main() { f(); int a = f(); return x + a - 4; }'
+try 5 'int x = 1; int main() { int x = 5; return x; }'
+try 0 'int x; int x; int main() { return x; }'
+tryfail 'int x; int y = x; int main() { return 0; }'
+
+try 3 'int main() { int a[5] = {[2] = 3}; return a[0] + a[1] + a[2] + a[3] + a[4]; }'
+try 12 'int main() { int a[5] = {1, [3] = 4, 7}; return a[0] + a[1] + a[2] + a[3] + a[4]; }'
+try 6 'int main() { int a[] = {[5] = 1}; return sizeof(a) / sizeof(int); }'
+try 2 'int main() { int a[3] = {1, [0] = 2}; return a[0]; }'
+try 9 'int main() { int a[2][2] = {[1][0] = 4, [0] = {2, 3}}; return a[0][0] + a[0][1] + a[1][0] + a[1][1]; }'
+try 5 'int main() { int a[2][2] = {[0] = {1, 2}, [0][1] = 4}; return a[0][0] + a[0][1] + a[1][0] + a[1][1]; }'
+try 1 'int main() { int a[2][2] = {[0] = {1, 2}, [0] = {1}}; return a[0][0] + a[0][1]; }'
+try 30 'struct P { int x; int y; int z; }; int main() { struct P p = {.z = 3, .x = 1}; return p.x * 10 + p.y + p.z * 5 + 5; }'
+try 8 'struct P { int x; int y; int z; }; int main() { struct P p = {.y = 3, 5}; return p.x + p.y + p.z; }'
+try 7 'struct P { int x; int y; }; struct Q { struct P p; int z; }; int main() { struct Q q = {.p.y = 3, .z = 4}; return q.p.x + q.p.y + q.z; }'
+try 10 'struct P { int x; int y; }; struct Q { struct P p; int z; }; int main() { struct Q q = {1, 2, .z = 3, .p.x = 5}; return q.p.x + q.p.y + q.z; }'
+try 3 'struct P { int x; int y; }; int main() { struct P ps[3] = {[1].y = 3}; return ps[0].x + ps[1].x + ps[1].y + ps[2].y; }'
+try 6 'struct S { int a; struct { int b; int c; }; }; int main() { struct S s = {.c = 4, .a = 2}; return s.a + s.b + s.c; }'
+try 66 'int main() { union { int a; char b[4]; } u = {.b = {66}}; return u.a; }'
+try 3 'int main() { union { char c; int i; } u = {.i = 256 + 3}; return u.c; }'
+try 15 'int a[5] = {[4] = 5, [1] = 10}; int main() { return a[0] + a[1] + a[2] + a[3] + a[4]; }'
+try 7 'struct P { int x; int y; } p = {.y = 7}; int main() { return p.x + p.y; }'
+try 3 'int main() { return (int){3}; }'
+try 7 'struct P { int x; int y; }; int main() { return (struct P){3, 4}.x + (struct P){3, 4}.y; }'
+try 5 'struct P { int x; int y; }; int main() { struct P p = (struct P){.y = 5}; return p.x + p.y; }'
+try 6 'int main() { int *p = (int[]){1, 2, 3}; return p[0] + p[1] + p[2]; }'
+try 12 'int main() { return sizeof((int[]){1, 2, 3}); }'
+try 12 'int main() { return sizeof (int[]){1, 2, 3}; }'
+try 9 'int main() { int *p = &(int){4}; *p = *p + 5; return *p; }'
+try 10 'int main() { int i; int s = 0; for (i = 0; i < 4; i++) { int *p = (int[2]){i}; s += p[0] + p[1]; p[1] = 9; } return s + 4; }'
+try 4 'struct P { int x; int y; }; int sum(struct P p) { return p.x + p.y; } int main() { return sum((struct P){1, 3}); }'
+tryfail 'int main() { int a[2] = {[2] = 1}; return 0; }'
+tryfail 'int main() { int x = {[0] = 1}; return 0; }'
+tryfail 'struct P { int x; }; int main() { struct P p = {.y = 1}; return 0; }'
+tryfail 'int main() { int a[2] = {.x = 1}; return 0; }'
+
+try 6 'int count() { static int n; n++; return n; } int main() { count(); count(); return count() * 2; }'
+try 15 'int count() { static int n = 10; return n++; } int main() { count(); count(); count(); count(); return count() + 1; }'
+try 3 'int f() { static int a[3] = {1, 2}; a[2]++; return a[2]; } int main() { f(); f(); return f(); }'
+try 7 'int f() { static int x = 3; { static int x = 4; x++; } return x + 4; } int main() { f(); return f(); }'
+try 11 'int f() { static int x = 1; return x++; } int g() { static int x = 10; return x; } int main() { f(); return f() + g() - 1; }'
+try 12 'int *p() { static int x = 5; return &x; } int main() { *p() = 12; return *p(); }'
+try 10 'static int ret3() { return 10; } int main() { return ret3(); }'
+try 10 'static int f(); int main() { return f(); } int f() { return 10; }'
+try 4 'static int x = 4; int main() { return x; }'
+try 7 'extern int ext_var; int main() { return ext_var; }'
+try 9 'extern int ext_var; int get_ext_var(); int main() { ext_var = 9; return get_ext_var(); }'
+try 6 'extern int ext_arr[]; int main() { return ext_arr[0] + ext_arr[1] + ext_arr[2]; }'
+try 7 'int main() { extern int ext_var; return ext_var; }'
+try 3 'extern int x; int main() { return x; } int x = 3;'
+try 5 'extern int a[]; int a[] = {1, 4}; int main() { return a[0] + a[1]; }'
+try 8 'int x = 3; int main() { extern int x; int y = x; { int x = 5; y = y + x; } return y; }'
+try 2 'extern int f(int); int f(int x) { return x + 1; } int main() { return f(1); }'
+tryfail 'int f() { return 0; } static int f(); int main() { return 0; }'
+tryfail 'int x; static int x; int main() { return 0; }'
+tryfail 'extern int x; double x; int main() { return 0; }'
+tryfail 'static extern int x; int main() { return 0; }'
+tryfail 'int main() { static int x = ret3(); return x; }'
+tryfail 'int main() { int y = 1; static int x = y; return x; }'
+tryfail 'int main() { extern int x = 1; return x; }'
+tryfail 'int f(static int x) { return x; } int main() { return 0; }'
+tryfail 'int main() { static int f(); return 0; }'
+
+try 5 'int main() { const int x = 5; return x; }'
+try 5 'int main() { int const x = 5; return x; }'
+try 3 'const int g = 3; int main() { return g; }'
+try 6 'const int a[3] = {1, 2, 3}; int main() { return a[0] + a[1] + a[2]; }'
+try 7 'int main() { int x = 3; int *const p = &x; *p = 7; return x; }'
+try 4 'int main() { int x = 4; const int *p = &x; return *p; }'
+try 2 'int main() { int x = 1; int y = 2; const int *p = &x; p = &y; return *p; }'
+try 1 'int f(const char *s) { return *s == 104; } int main() { char *s = "hi"; return f(s); }'
+try 3 'int main() { volatile int x = 3; return x; }'
+try 8 'int main() { int x = 8; int *restrict p = &x; return *p; }'
+try 9 'typedef const int cint; int main() { cint x = 9; return x; }'
+try 6 'struct P { const int x; int y; }; int main() { struct P p = {1, 5}; p.y = 5; return p.x + p.y; }'
+try 4 'int f(const int x) { return x; } int main() { return f(4); }'
+try 5 'int main() { const int x = 5; int y = x; y++; return y - 1; }'
+tryfail 'int main() { const int x = 5; x = 3; return x; }'
+tryfail 'int main() { const int x = 5; x++; return x; }'
+tryfail 'int main() { const int x = 5; x += 1; return x; }'
+tryfail 'int main() { int x = 3; const int *p = &x; *p = 7; return x; }'
+tryfail 'int main() { int x = 3; int y = 4; int *const p = &x; p = &y; return *p; }'
+tryfail 'int main() { const int x = 3; int *p = &x; return *p; }'
+tryfail 'int f(char *s) { return 0; } int main() { const char *s = "hi"; return f(s); }'
+tryfail 'int *f(const int *p) { return p; } int main() { return 0; }'
+tryfail 'struct P { int x; }; int main() { const struct P p = {1}; p.x = 2; return 0; }'
+tryfail 'struct P { const int x; }; int main() { struct P p = {1}; struct P q = {2}; p = q; return 0; }'
+tryfail 'const int g = 3; int main() { g = 4; return g; }'
+tryfail 'int main() { restrict int x = 1; return x; }'
+tryfail 'int main() { const int a[2] = {1, 2}; a[0] = 3; return 0; }'
+
+try 5 'int x = 5; int *p = &x; int main() { return *p; }'
+try 3 'int a[4] = {1, 2, 3, 4}; int *p = &a[2]; int main() { return *p; }'
+try 4 'int a[4] = {1, 2, 3, 4}; int *p = a + 3; int main() { return *p; }'
+try 2 'int a[4] = {1, 2, 3, 4}; int *p = &a[3] - 2; int main() { return *p; }'
+try 98 'char *s = "abc"; int main() { return s[1]; }'
+try 100 'char *s[] = {"ab", "cd"}; int main() { return s[1][1]; }'
+try 111 'char *s = "hello" + 1; int main() { return s[3]; }'
+try 7 'struct P { int x; int y; } g = {3, 7}; int *q = &g.y; int main() { return *q; }'
+try 8 'struct S { int n; int a[3]; } s = {1, {2, 8, 4}}; int *p = s.a + 1; int main() { return *p; }'
+try 6 'int a[2][3] = {{1, 2, 3}, {4, 5, 6}}; int *p = a[1] + 2; int main() { return *p; }'
+try 5 'int a[2][3] = {{1, 2, 3}, {4, 5, 6}}; int (*p)[3] = a + 1; int main() { return p[0][1]; }'
+try 4 'int f(int x) { return x + 1; } int (*fp)(int) = f; int main() { return fp(3); }'
+try 2 'int x; int y; int *p = 1 ? &y : &x; int main() { y = 2; return *p; }'
+try 0 'int *p = 0; int main() { return p != 0; }'
+try 9 'int main() { static int x = 9; static int *p = &x; return *p; }'
+try 6 'int *p = (int[]){1, 2, 3}; int main() { return p[0] + p[1] + p[2]; }'
+try 7 'extern int ext_var; int *p = &ext_var; int main() { return *p; }'
+try 3 'int x = 3; const int *const p = &x; int main() { return *p; }'
+try 2 'int a[4]; int n = &a[3] - &a[1]; int main() { return n; }'
+try 8 'int a[(3 << 2) - (7 % 4) * 4 + (int)2.9 * 2]; int main() { return sizeof(a) / sizeof(a[0]) + 4; }'
+try 2 'int main() { switch (4) { case (char)259: return 1; case 0 ? 3 : 4: return 2; } return 0; }'
+try 1 'enum { A = -2147483647 - 1, B = ~0 }; int main() { return A < 0 && B == -1; }'
+try 4 '_Static_assert(sizeof(int) == 4, "int is 4 bytes"); int main() { _Static_assert(sizeof(char) == 1); return 4; }'
+try 3 'struct P { int x; int y; }; _Static_assert(sizeof(struct P) == 8, "P"); int main() { return 3; }'
+tryfail '_Static_assert(sizeof(int) == 8, "int is 8 bytes"); int main() { return 0; }'
+tryfail 'int main() { int n = 1; _Static_assert(n, "n"); return 0; }'
+tryfail 'int main() { _Static_assert(1, 2); return 0; }'
+tryfail 'int x = 1 / 0; int main() { return x; }'
+tryfail 'int a[5 % (2 - 2)]; int main() { return 0; }'
+tryfail 'int main() { int y; static int *p = &y; return 0; }'
+tryfail 'int x; int y = (int)&x; int main() { return y; }'
+tryfail 'int a[2]; int b[2]; int n = &a[1] - &b[0]; int main() { return n; }'
+tryfail 'int x = 1; int *p = &x + x; int main() { return 0; }'
+
+tryfail 'struct S { int a; }; int main() { struct S s; s = 1; return 0; }'
+tryfail 'struct S { int a; }; int main() { struct S s; int x; x = s; return 0; }'
+tryfail 'struct S { int a; }; struct T { int a; }; int main() { struct S s; struct T t; s = t; return 0; }'
+tryfail 'union U { int a; }; int main() { union U u; u = 2; return 0; }'
+tryfail 'int main() { int *p; p = 3; return 0; }'
+tryfail 'int main() { int *p; double d = 1.0; p = d; return 0; }'
+try 0 'int main() { int *p; p = 0; return p != 0; }'
+try 3 'struct S { int a; }; int main() { struct S s; struct S t; t.a = 3; s = t; return s.a; }'
+
+try 7 'int add3(int x) { return x + 3; } int (*get())(int) { return add3; } int main() { return get()(4); }'
+try 7 'int add3(int x) { return x + 3; } int (*get(int n))(int); int main() { return get(0)(4); } int (*get(int n))(int) { return add3; }'
+try 9 'int minus(int x, int y) { return x - y; } int (*pick(int (*f)(int, int), int k))(int, int) { return k ? f : 0; } int main() { return pick(minus, 1)(12, 3); }'
+try 5 'int a[3] = {3, 4, 5}; int (*row())[3] { return &a; } int main() { return (*row())[2]; }'
+tryfail 'int (*get())(int) { return 0; } int main() { return get()(1, 2); }'
+
+try 3 'int plus(); int main() { return plus(1, 2); } int plus(int x, int y) { return x + y; }'
+try 3 'int plus(); int plus(int x, int y) { return x + y; } int main() { return plus(1, 2); }'
+try 5 'int five() { return 5; } int main() { return five(); }'
+try 5 'int five(void) { return 5; } int main() { return five(); }'
+try 4 'int (*fp)(); int twice(int x) { return x * 2; } int main() { fp = twice; return fp(2); }'
+try 6 'int sum(int x, int y, int z) { return x + y + z; } int main() { int sum(); return sum(1, 2, 3); }'
+tryfail 'int five(void) { return 5; } int main() { return five(1); }'
+tryfail 'int plus(); int plus(int x, int y) { return x + y; } int main() { return plus(1); }'
+tryfail 'int f(); int f(char c) { return c; } int main() { return 0; }'
+tryfail 'int f(); int f(int x, ...) { return x; } int main() { return 0; }'
+tryfail 'int f(void x) { return 0; } int main() { return 0; }'
+tryfail 'int main() { void x; return 0; }'
+
+try 1 'int x; int x = 1; int main() { return x; }'
+try 2 'int x = 2; int x; int main() { return x; }'
+try 3 'int x; int main() { x = 3; return x; } int x;'
+try 4 'static int x; static int x = 4; int main() { return x; }'
+try 5 'int a[]; int a[2] = {2, 3}; int main() { return a[0] + a[1]; }'
+try 6 'struct S s; struct S { int a; int b; }; int main() { s.a = 2; s.b = 4; return s.a + s.b; }'
+try 7 'extern int y; int y; int main() { y = 7; return y; }'
+tryfail 'int x = 1; int x = 2; int main() { return x; }'
+tryfail 'int a[]; int main() { return 0; }'
+tryfail 'static int x; int x = 1; int main() { return x; }'
+tryfail 'int x; char x; int main() { return 0; }'
+
+try 3 'struct S { char *s; int x; } a[] = {"ab", 1, "cd", 2}; int main() { return a[1].x + (a[1].s[1] == 100); }'
+try 7 'struct P { int x; int y; }; struct Q { struct P p; int z; }; int main() { struct P p = {3, 4}; struct Q q = {p, 5}; return q.p.x + q.p.y; }'
+try 9 'struct P { int x; int y; }; struct Q { struct P p; int z; }; int main() { struct Q q = {(struct P){2, 3}, 4}; return q.p.x + q.p.y + q.z; }'
+try 44 'struct P { int x; int y; }; struct Q { struct P p; int z; }; int main() { struct Q q = {(char)300, 2, 3}; return q.p.x; }'
+try 3 'struct S { char s[4]; int x; } a[] = {"ab", 1, "cd", 2}; int main() { return a[1].x + (a[1].s[1] == 100); }'
+tryfail 'struct P { int x; }; int main() { struct P p = 1; return 0; }'
+tryfail 'struct P { int x; }; struct P p = 1; int main() { return 0; }'
+tryfail 'struct P { int x; }; struct Q { int x; }; int main() { struct Q q = {1}; struct P p = q; return 0; }'
+tryfail 'struct P { int x; }; int main() { struct P p = {1}; int x = p; return x; }'
+tryfail 'int main() { int *p = 3; return 0; }'
+
+tryfail 'int main() { const char s[] = "abc"; s[0] = 65; return 0; }'
+tryfail 'const char s[] = "abc"; int main() { s[0] = 65; return 0; }'
+tryasm '.section .rodata .align 1 s: ' 'const char s[] = "abc"; int main() { return s[0]; }'
+tryasm '.section .rodata .align 4 a: ' 'const int a[] = {1, 2}; int main() { return a[0]; }'
+tryasm '.data .align 1 s: ' 'char s[] = "abc"; int main() { return s[0]; }'
+
+try 0 'int main() { char *s = "a"; char **pp = &s; const char **q = pp; return 0; }'
+try 1 'int main() { char *s = "a"; char **pp = &s; const char *const *q = pp; return **q == 97; }'
+try 2 'struct S { int a; int b; }; int main() { struct S s = {1, 2}; int *p = &s; return p[1]; }'
+tryfail 'int main() { const char *s = "a"; const char **q = &s; char **pp = q; return 0; }'
+tryfail 'int main() { const int x = 1; const int *p = &x; int **pp = &p; return 0; }'
+tryfail 'int f(char **pp) { return 0; } int main() { const char *s = "a"; return f(&s); }'
+
+try 2 'int main() { float f = 5; return half(f); } int half(double d) { return d / 2; }'
+try 3 'int main() { char c = 3; return ident(c); } int ident(int x) { return x; }'
+
+tryfail 'int *f() { return 5; } int main() { return 0; }'
+tryfail 'struct S { int a; }; int f() { struct S s = {1}; return s; } int main() { return f(); }'
+try 0 'int *f() { return 0; } int main() { return f() != 0; }'
+
+# include/stdarg.h is expanded by the preprocessor of gcc, because tvcc has no preprocessor
+try 6 "$(gcc -E -P -nostdinc -Iinclude -xc - <<'END'
+#include <stdarg.h>
+int sum(int n, ...) { va_list ap; va_start(ap, n); int s = 0; int i; for (i = 0; i < n; i++) s += va_arg(ap, int); va_end(ap); return s; }
+int main() { return sum(3, 1, 2, 3); }
+END
+)"
+
+try 7 'int f(int n, ...) { int __va_area__ = 3; __builtin_va_list ap; __builtin_va_start(ap, n); return __va_area__ + __builtin_va_arg(ap, int); } int main() { return f(1, 4); }'
+tryfail 'int f(int n) { __builtin_va_list ap; __builtin_va_start(ap, n); return 0; } int main() { return f(1); }'
+
+try 8 'struct S { int a, b; }; struct S f(); int n = sizeof(f()); int main() { return n; }'
+try 12 'struct S { int a, b, c; }; struct S f(); int main() { return sizeof(f()); }'
+tryfail 'struct S { int a, b; }; struct S f(); struct S s = f(); int main() { return 0; }'
+
+try 15 'int main() { unsigned x = -1; return x >> 28; }'
+try 1 'int main() { unsigned int x = -1; return x > 0; }'
+try 1 'int main() { int i = -1; unsigned u = 1; return i > u; }'
+try 0 'int main() { unsigned char c = 255; int x = c; return x < 0; }'
+try 255 'int main() { unsigned char c = -1; return c; }'
+try 1 'int main() { unsigned char c = 200; char d = 200; return c != d; }'
+try 4 'int main() { return sizeof(unsigned) + sizeof(signed char) * 0; }'
+try 1 'int main() { return sizeof(unsigned char); }'
+try 6 'int main() { unsigned x = 0; x = x - 1; return x / 715827882; }'
+try 3 'int main() { unsigned x = -1; return x % 4; }'
+try 2 'int main() { unsigned x = 0; x--; return (x + 3) >> 0; }'
+try 1 'int main() { unsigned x = 1 << 31; return (x << 1) == 0; }'
+try 15 'int main() { unsigned x = -1; x >>= 28; return x; }'
+try 1 'int main() { return ~(unsigned)0 > 0; }'
+try 1 'int main() { unsigned char c = 1; return -c < 0; }'
+try 1 'int main() { return -8 >> (unsigned)1 == -4; }'
+try 1 'int main() { double d = (unsigned)-1; return d > 0; }'
+try 1 'double d = (unsigned)-1; int main() { return d > 4294967294.0; }'
+try 15 'int a[(unsigned)-1 >> 28]; int main() { return sizeof(a) / 4; }'
+try 1 'int main() { switch (1) { case (unsigned)-1 / 2 > 0: return 1; } return 0; }'
+try 1 'unsigned f(unsigned char c) { return c; } int main() { return f(255) == 255; }'
+try 7 'int main() { int unsigned x = 7; unsigned char const c = 0; return x + c; }'
+try 1 'typedef int T; int main() { unsigned T = 1; return T; }'
+tryfail 'int main() { unsigned double d; return 0; }'
+tryfail 'int main() { signed unsigned x; return 0; }'
+try 1 'int main() { unsigned x = -1; unsigned *p = &x; return *p == x; }'
+
+try 4 'int g; int h = sizeof(g++); int main() { return h; }'
+try 8 'int g; int h = sizeof(g += 1) + sizeof(--g); int main() { return h + g; }'
+tryfail 'int g; int h = g++; int main() { return h; }'
+try 4 'int main() { int x = 1; int n = sizeof(x++); return n + x - 1; }'
 
+try 3 'int main() { int x = 3; int y = 4; const int *p = &x; int *q = &y; return *(1 ? (const int *)p : (int *)q); }'
+try 4 'int main() { int x = 3; int y = 4; return *(0 ? (const int *)&x : (volatile int *)&y); }'
+tryfail 'int main() { int x = 3; int y = 4; int *r = 1 ? (const int *)&x : &y; return *r; }'
+tryfail 'int main() { int x = 3; const int *p = &x; *(1 ? p : &x) = 5; return x; }'
+tryfail 'int main() { int x = 3; char c = 4; return *(1 ? &x : &c); }'
 
 echo OK